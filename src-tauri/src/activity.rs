@@ -1,29 +1,46 @@
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
 use user_idle::UserIdle;
+use crate::audio::AudioManager;
+use crate::error::GaweError;
+use crate::events::{self, GaweEvent};
+use crate::power::PowerEvent;
+use crate::settings::{self, ActivitySettings};
+use crate::sounds::SoundEvent;
 
 pub struct ActivityState {
     pub is_enabled: Arc<Mutex<bool>>,
     pub threshold_seconds: Arc<Mutex<u64>>,
     pub notified: Arc<Mutex<bool>>,
+    /// While locked or suspended the user is away on purpose, not idle.
+    pub is_away: Arc<Mutex<bool>>,
 }
 
 pub struct ActivityManager {
     pub state: Arc<ActivityState>,
 }
 
+impl Default for ActivityManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ActivityManager {
     pub fn new() -> Self {
-        let is_enabled = Arc::new(Mutex::new(false));
-        let threshold_seconds = Arc::new(Mutex::new(300)); // Default 5 minutes
+        let defaults = ActivitySettings::default();
+        let is_enabled = Arc::new(Mutex::new(defaults.enabled));
+        let threshold_seconds = Arc::new(Mutex::new(defaults.threshold_seconds));
         let notified = Arc::new(Mutex::new(false));
+        let is_away = Arc::new(Mutex::new(false));
 
         let state = Arc::new(ActivityState {
             is_enabled: is_enabled.clone(),
             threshold_seconds: threshold_seconds.clone(),
             notified: notified.clone(),
+            is_away: is_away.clone(),
         });
 
         Self { state }
@@ -37,7 +54,8 @@ impl ActivityManager {
                 tokio::time::sleep(Duration::from_secs(5)).await;
                 
                 let is_enabled = *state.is_enabled.lock().unwrap();
-                if !is_enabled {
+                let is_away = *state.is_away.lock().unwrap();
+                if !is_enabled || is_away {
                     continue;
                 }
 
@@ -52,12 +70,13 @@ impl ActivityManager {
                         let mut notified_guard = state.notified.lock().unwrap();
                         if !*notified_guard {
                             // Trigger notification
-                            let _ = app.emit("activity-warning", ());
+                            events::emit(&app, GaweEvent::ActivityWarning { idle_seconds });
                             let _ = app.notification()
                                 .builder()
                                 .title("Are you still there?")
                                 .body("We haven't detected any activity for a while. Stay focused!")
                                 .show();
+                            app.state::<AudioManager>().play_sound(SoundEvent::Warning);
                             
                             *notified_guard = true;
                         }
@@ -73,6 +92,39 @@ impl ActivityManager {
         });
     }
 
+    pub fn handle_power_event(&self, event: PowerEvent) {
+        let away = matches!(event, PowerEvent::Locked | PowerEvent::Suspending);
+        if let Ok(mut a) = self.state.is_away.lock() {
+            *a = away;
+        }
+        if !away {
+            // Start a fresh idle period once the user is back
+            if let Ok(mut n) = self.state.notified.lock() {
+                *n = false;
+            }
+        }
+    }
+
+    pub fn settings(&self) -> ActivitySettings {
+        ActivitySettings {
+            enabled: *self.state.is_enabled.lock().unwrap(),
+            threshold_seconds: *self.state.threshold_seconds.lock().unwrap(),
+        }
+    }
+
+    pub fn apply_settings(&self, settings: &ActivitySettings) {
+        self.set_enabled(settings.enabled);
+        self.set_threshold(settings.threshold_seconds);
+    }
+
+    /// Applies, persists and broadcasts new settings.
+    pub fn update_settings(&self, app: &AppHandle, settings: ActivitySettings) -> Result<(), GaweError> {
+        self.apply_settings(&settings);
+        settings::save(app, settings::ACTIVITY_KEY, &settings)?;
+        events::emit(app, GaweEvent::ActivitySettingsChanged(settings));
+        Ok(())
+    }
+
     pub fn set_enabled(&self, enabled: bool) {
         if let Ok(mut e) = self.state.is_enabled.lock() {
             *e = enabled;
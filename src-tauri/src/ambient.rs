@@ -0,0 +1,97 @@
+use std::time::Duration;
+use rodio::source::noise::{Brownian, Pink, WhiteUniform};
+use rodio::source::SineWave;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_RATE: SampleRate = 44_100;
+
+/// Procedurally generated background sounds, selected by name from the UI
+/// (e.g. "pink", "binaural-focus").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AmbientSound {
+    White,
+    Pink,
+    Brown,
+    Rain,
+    /// 14 Hz beta beat over a 200 Hz carrier; best with headphones.
+    BinauralFocus,
+    /// 10 Hz alpha beat over a 180 Hz carrier; best with headphones.
+    BinauralRelax,
+}
+
+impl AmbientSound {
+    pub const ALL: [AmbientSound; 6] = [
+        AmbientSound::White,
+        AmbientSound::Pink,
+        AmbientSound::Brown,
+        AmbientSound::Rain,
+        AmbientSound::BinauralFocus,
+        AmbientSound::BinauralRelax,
+    ];
+
+    /// An endless source for this sound. Levels are balanced so switching
+    /// between sounds at the same volume doesn't jump in loudness.
+    pub fn source(self) -> Box<dyn Source + Send> {
+        match self {
+            AmbientSound::White => Box::new(WhiteUniform::new(SAMPLE_RATE).amplify(0.25)),
+            AmbientSound::Pink => Box::new(Pink::new(SAMPLE_RATE).amplify(0.35)),
+            AmbientSound::Brown => Box::new(Brownian::new(SAMPLE_RATE).amplify(0.3)),
+            AmbientSound::Rain => {
+                // Hiss of the drops over a low rumble
+                let hiss = Pink::new(SAMPLE_RATE).high_pass(800).amplify(0.35);
+                let rumble = Brownian::new(SAMPLE_RATE).low_pass(400).amplify(0.2);
+                Box::new(hiss.mix(rumble))
+            }
+            AmbientSound::BinauralFocus => Box::new(BinauralBeat::new(200.0, 14.0).amplify(0.2)),
+            AmbientSound::BinauralRelax => Box::new(BinauralBeat::new(180.0, 10.0).amplify(0.2)),
+        }
+    }
+}
+
+/// Two sine tones, one per ear, `beat_hz` apart.
+pub struct BinauralBeat {
+    left: SineWave,
+    right: SineWave,
+    next_is_left: bool,
+}
+
+impl BinauralBeat {
+    pub fn new(carrier_hz: f32, beat_hz: f32) -> Self {
+        Self {
+            left: SineWave::new(carrier_hz),
+            right: SineWave::new(carrier_hz + beat_hz),
+            next_is_left: true,
+        }
+    }
+}
+
+impl Iterator for BinauralBeat {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        // Samples are interleaved left, right, left, ...
+        let sample = if self.next_is_left { self.left.next() } else { self.right.next() };
+        self.next_is_left = !self.next_is_left;
+        sample
+    }
+}
+
+impl Source for BinauralBeat {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        2
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.left.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
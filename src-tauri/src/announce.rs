@@ -0,0 +1,130 @@
+use std::env;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager};
+
+/// Folder inside the app's resources holding one clip per spoken word.
+const VOICE_DIR: &str = "voice";
+const CLIP_EXTENSION: &str = "ogg";
+
+/// Timer moments that can be spoken aloud.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Announcement {
+    WorkStart,
+    BreakStart,
+    FiveMinutesLeft,
+    OneMinuteLeft,
+    SessionComplete,
+}
+
+impl Announcement {
+    pub const ALL: [Announcement; 5] = [
+        Announcement::WorkStart,
+        Announcement::BreakStart,
+        Announcement::FiveMinutesLeft,
+        Announcement::OneMinuteLeft,
+        Announcement::SessionComplete,
+    ];
+
+    /// The words of the phrase, each naming a clip in the voice folder.
+    pub fn words(self) -> &'static [&'static str] {
+        match self {
+            Announcement::WorkStart => &["focus", "time"],
+            Announcement::BreakStart => &["break", "time"],
+            Announcement::FiveMinutesLeft => &["five", "minutes", "left"],
+            Announcement::OneMinuteLeft => &["one", "minute", "left"],
+            Announcement::SessionComplete => &["session", "complete"],
+        }
+    }
+
+    /// The warning due when `remaining_seconds` are left in a task.
+    pub fn for_remaining(remaining_seconds: u32) -> Option<Announcement> {
+        match remaining_seconds {
+            300 => Some(Announcement::FiveMinutesLeft),
+            60 => Some(Announcement::OneMinuteLeft),
+            _ => None,
+        }
+    }
+
+    pub fn phrase(self) -> String {
+        self.words().join(" ")
+    }
+
+    /// Clip files for the phrase in speaking order, or `None` if any is missing.
+    pub fn clips(self, app: &AppHandle) -> Option<Vec<PathBuf>> {
+        self.words()
+            .iter()
+            .map(|word| {
+                let clip = PathBuf::from(VOICE_DIR).join(word).with_extension(CLIP_EXTENSION);
+                app.path().resolve(clip, BaseDirectory::Resource).ok().filter(|path| path.exists())
+            })
+            .collect()
+    }
+}
+
+/// Says `phrase` with the system speech synthesizer after `delay`, for
+/// installs without voice clips. Returns `false` when none is installed.
+pub fn speak(phrase: &str, volume: f32, delay: Duration) -> bool {
+    let Some(mut command) = speech_commands(phrase, volume).into_iter().find(|c| on_path(c.get_program())) else {
+        return false;
+    };
+    thread::spawn(move || {
+        thread::sleep(delay);
+        if let Err(e) = command.status() {
+            eprintln!("Failed to run speech synthesizer: {}", e);
+        }
+    });
+    true
+}
+
+fn on_path(program: &OsStr) -> bool {
+    let Some(paths) = env::var_os("PATH") else {
+        return false;
+    };
+    env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() || candidate.with_extension("exe").is_file()
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn speech_commands(phrase: &str, volume: f32) -> Vec<Command> {
+    let mut say = Command::new("say");
+    say.arg(format!("[[volm {:.2}]] {}", volume, phrase));
+    vec![say]
+}
+
+#[cfg(target_os = "windows")]
+fn speech_commands(phrase: &str, volume: f32) -> Vec<Command> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; $s.Volume = {}; $s.Speak('{}')",
+        (volume * 100.0).round() as u32,
+        phrase,
+    );
+    let mut powershell = Command::new("powershell");
+    powershell.args(["-NoProfile", "-NonInteractive", "-Command", &script]).creation_flags(CREATE_NO_WINDOW);
+    vec![powershell]
+}
+
+/// speech-dispatcher first since it follows the desktop's voice settings,
+/// then eSpeak for systems without it.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn speech_commands(phrase: &str, volume: f32) -> Vec<Command> {
+    let mut spd_say = Command::new("spd-say");
+    spd_say.args(["-i", &((volume * 200.0).round() as i32 - 100).to_string(), phrase]);
+    let amplitude = ((volume * 100.0).round() as u32).to_string();
+    let mut espeak_ng = Command::new("espeak-ng");
+    espeak_ng.args(["-a", &amplitude, phrase]);
+    let mut espeak = Command::new("espeak");
+    espeak.args(["-a", &amplitude, phrase]);
+    vec![spd_say, espeak_ng, espeak]
+}
@@ -1,162 +1,1226 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
+use std::io::{BufReader, ErrorKind};
+use std::path::Path;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-use rodio::{Decoder, OutputStreamBuilder, Sink, Source};
-use rodio::source::SineWave;
+use std::time::{Duration, Instant};
+use rodio::{Decoder, Sink, Source};
+use rodio::source::{Buffered, SineWave, Zero};
+use tauri::{AppHandle, Manager};
+use crate::ambient::AmbientSound;
+use crate::announce::{self, Announcement};
+use crate::error::GaweError;
+use crate::events::{self, GaweEvent};
+use crate::library::LibraryManager;
+use crate::models::{TaskAudio, TaskType};
+use crate::output::{DeviceSnapshot, Output};
+use crate::playlist::{self, Playlist, RepeatMode};
+use crate::settings::{self, AnnouncementSettings, FadeSettings, SoundSettings, TickSettings};
+use crate::sounds::{SoundChoice, SoundEvent};
+use crate::tick::TickSample;
+
+/// How often the audio thread checks whether the current track has ended.
+const TRACK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Volume update rate while a fade is in progress.
+const FADE_STEP_INTERVAL: Duration = Duration::from_millis(20);
+/// How quickly music dips under a notification sound and comes back afterwards.
+const DUCK_ATTACK: Duration = Duration::from_millis(250);
+const DUCK_RELEASE: Duration = Duration::from_millis(800);
+/// Gap left for a bell that is still ringing before a phrase is spoken.
+const ANNOUNCE_DELAY: Duration = Duration::from_millis(1200);
+/// Rough length of a phrase from the system speech synthesizer, used to keep
+/// the music ducked while it talks.
+const SPEECH_STARTUP: Duration = Duration::from_millis(400);
+const SPOKEN_WORD: Duration = Duration::from_millis(450);
+/// How often the position of a playing track is saved, so a crash loses little.
+const POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Positions this close to either end of a track aren't worth resuming from.
+const RESUME_MARGIN: Duration = Duration::from_secs(5);
+/// Resume points kept; the least recently played track is forgotten first.
+const MAX_POSITIONS: usize = 100;
+/// File tick samples are cut to this length so ticks can't pile up.
+const MAX_TICK_LENGTH: Duration = Duration::from_millis(500);
+/// How often the device watcher looks for a lost, changed or newly plugged-in device.
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// How long callers wait for the audio thread to answer before giving up.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind")]
+pub enum AudioError {
+    FileNotFound { path: String },
+    UnsupportedFormat { path: String },
+    Io { path: String, message: String },
+    EmptyPlaylist,
+    NotPlaying,
+    SeekFailed { message: String },
+    NoOutputDevice { message: String },
+    DeviceNotFound { name: String },
+    /// The audio thread stopped or didn't answer in time.
+    Unavailable,
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AudioError::FileNotFound { path } => write!(f, "File not found: {}", path),
+            AudioError::UnsupportedFormat { path } => write!(f, "Unsupported audio format: {}", path),
+            AudioError::Io { path, message } => write!(f, "Failed to read {}: {}", path, message),
+            AudioError::EmptyPlaylist => write!(f, "No playable audio files found"),
+            AudioError::NotPlaying => write!(f, "No track is loaded"),
+            AudioError::SeekFailed { message } => write!(f, "Failed to seek: {}", message),
+            AudioError::NoOutputDevice { message } => write!(f, "No audio output device: {}", message),
+            AudioError::DeviceNotFound { name } => write!(f, "Audio device not found: {}", name),
+            AudioError::Unavailable => write!(f, "Audio playback is unavailable"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+#[derive(Clone, serde::Serialize)]
+pub struct AudioStatus {
+    pub output_available: bool,
+    /// Why the output device couldn't be opened, if it couldn't.
+    pub output_error: Option<String>,
+    pub output_device: Option<String>,
+    pub is_playing: bool,
+    pub is_paused: bool,
+    pub now_playing: Option<NowPlaying>,
+    pub position_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub ambient: Option<AmbientSound>,
+}
 
 enum AudioCommand {
-    Play(String),
-    PlayBell,
+    AttachApp(AppHandle),
+    Play(Vec<String>, mpsc::Sender<Result<(), AudioError>>),
+    Status(mpsc::Sender<AudioStatus>),
+    Seek(Duration, mpsc::Sender<Result<(), AudioError>>),
+    PlaySound(SoundEvent),
+    PreviewSound(SoundChoice, mpsc::Sender<Result<(), AudioError>>),
+    SetSounds(SoundSettings),
+    Announce(Announcement),
+    SetAnnouncements(AnnouncementSettings),
+    /// Pauses playing music, or resumes paused music.
     Pause,
+    /// Pauses playing music and leaves paused music alone.
+    PauseIfPlaying,
     Stop,
+    Next,
+    Previous,
+    SetRepeat(RepeatMode),
+    SetShuffle(bool),
     SetVolume(f32),
+    PlayAmbient(AmbientSound),
+    StopAmbient,
+    PlayTaskAudio(TaskAudio),
+    SetAmbientVolume(f32),
+    SetFades(FadeSettings),
+    Tick(TaskType),
+    SetTick(TickSettings),
+    SetOutputDevice(Option<String>, mpsc::Sender<Result<(), AudioError>>),
+    /// Remembers the device without waiting; the next device check switches to it.
+    PreferOutputDevice(Option<String>),
+    /// Sent every few seconds by the device watcher thread.
+    DevicesChecked(DeviceSnapshot),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NowPlaying {
+    pub path: String,
+    pub title: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+/// Resume points in milliseconds by file path, most recently played last.
+#[derive(Default)]
+struct Positions {
+    entries: Vec<(String, u64)>,
+}
+
+impl Positions {
+    fn load(app: &AppHandle) -> Self {
+        let entries = settings::load::<Vec<(String, u64)>>(app, settings::POSITIONS_KEY)
+            // Earlier versions kept an unordered map
+            .or_else(|| {
+                settings::load::<HashMap<String, u64>>(app, settings::POSITIONS_KEY)
+                    .map(|positions| positions.into_iter().collect())
+            })
+            .unwrap_or_default();
+        let mut positions = Self { entries };
+        positions.trim();
+        positions
+    }
+
+    fn get(&self, path: &str) -> Option<u64> {
+        self.entries.iter().find(|(p, _)| p == path).map(|&(_, ms)| ms)
+    }
+
+    /// Records or (with `None`) forgets `path`. Returns whether anything changed.
+    fn set(&mut self, path: &str, ms: Option<u64>) -> bool {
+        if self.get(path) == ms {
+            return false;
+        }
+        self.entries.retain(|(p, _)| p != path);
+        if let Some(ms) = ms {
+            self.entries.push((path.to_string(), ms));
+            self.trim();
+        }
+        true
+    }
+
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(MAX_POSITIONS);
+        self.entries.drain(..excess);
+    }
+}
+
+/// Writes resume points to the store on a thread of its own, so the disk
+/// write never holds up a fade. Only the newest waiting snapshot is written.
+fn spawn_position_saver(app: AppHandle) -> mpsc::Sender<Vec<(String, u64)>> {
+    let (tx, rx) = mpsc::channel::<Vec<(String, u64)>>();
+    thread::spawn(move || {
+        while let Ok(mut positions) = rx.recv() {
+            while let Ok(newer) = rx.try_recv() {
+                positions = newer;
+            }
+            if let Err(e) = settings::save(&app, settings::POSITIONS_KEY, &positions) {
+                eprintln!("Failed to save playback positions: {}", e);
+            }
+        }
+    });
+    tx
+}
+
+/// A linear volume change over time, stepped by the audio thread loop.
+struct Ramp {
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+    /// Pause the sink once silent, for fade-out-then-pause.
+    pause_when_done: bool,
+}
+
+impl Ramp {
+    fn new(from: f32, to: f32, duration: Duration) -> Self {
+        Self { from, to, started: Instant::now(), duration, pause_when_done: false }
+    }
+
+    fn level(&self) -> f32 {
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let progress = (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.0);
+        self.from + (self.to - self.from) * progress
+    }
+
+    fn is_done(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+}
+
+/// State owned by the audio thread.
+struct Player {
+    /// `None` until a device could be opened; retried from the thread loop.
+    output: Option<Output>,
+    /// Why the last attempt to open a device failed.
+    output_error: Option<String>,
+    /// Device picked by the user; `None` follows the system default.
+    preferred_device: Option<String>,
+    /// What the device watcher saw last, so the audio thread never scans devices itself.
+    devices: DeviceSnapshot,
+    sink: Option<Sink>,
+    /// Fade applied to `sink`, if one is in progress.
+    ramp: Option<Ramp>,
+    /// Previous tracks still fading out; stopped once silent.
+    fading_out: Vec<(Sink, Ramp)>,
+    /// Gain applied on top of `ramp`/`volume` while notifications play; `None` means 1.0.
+    duck: Option<Ramp>,
+    /// Bell and notification sounds still playing; music stays ducked until they finish.
+    notification_sinks: Vec<Sink>,
+    /// Length of the current track, when the decoder knows it; used to start crossfades early.
+    track_duration: Option<Duration>,
+    fades: FadeSettings,
+    sounds: SoundSettings,
+    announcements: AnnouncementSettings,
+    /// What the current playlist was built from, so a task asking for the same music doesn't restart it.
+    sources: Option<Vec<String>>,
+    playlist: Option<Playlist>,
+    volume: f32,
+    repeat: RepeatMode,
+    shuffle: bool,
+    /// Generated background sound, mixed independently of the music sink.
+    ambient_sink: Option<Sink>,
+    ambient_volume: f32,
+    ambient: Option<AmbientSound>,
+    now_playing: Option<NowPlaying>,
+    tick: TickSettings,
+    /// Decoded once so every tick is a cheap clone.
+    tick_source: Buffered<Box<dyn Source + Send>>,
+    tick_sink: Option<Sink>,
+    positions: Positions,
+    last_position_save: Instant,
+    /// Hands position snapshots to the thread that writes them to the store.
+    position_saver: Option<mpsc::Sender<Vec<(String, u64)>>>,
+    app: Option<AppHandle>,
+}
+
+impl Player {
+    fn new() -> Self {
+        Self {
+            output: None,
+            output_error: None,
+            preferred_device: None,
+            devices: DeviceSnapshot::default(),
+            sink: None,
+            ramp: None,
+            fading_out: Vec::new(),
+            duck: None,
+            notification_sinks: Vec::new(),
+            track_duration: None,
+            fades: FadeSettings::default(),
+            sounds: SoundSettings::default(),
+            announcements: AnnouncementSettings::default(),
+            sources: None,
+            playlist: None,
+            volume: 0.5,
+            repeat: RepeatMode::default(),
+            shuffle: false,
+            ambient_sink: None,
+            ambient_volume: 0.5,
+            ambient: None,
+            now_playing: None,
+            tick: TickSettings::default(),
+            tick_source: tick_source(&TickSample::Mechanical),
+            tick_sink: None,
+            positions: Positions::default(),
+            last_position_save: Instant::now(),
+            position_saver: None,
+            app: None,
+        }
+    }
+
+    fn handle(&mut self, command: AudioCommand) {
+        match command {
+            AudioCommand::AttachApp(app) => {
+                self.positions = Positions::load(&app);
+                self.position_saver = Some(spawn_position_saver(app.clone()));
+                self.app = Some(app);
+            }
+            AudioCommand::Play(sources, reply) => {
+                let _ = reply.send(self.play_sources(sources, self.fades.fade_in()));
+            }
+            AudioCommand::Status(reply) => {
+                let _ = reply.send(AudioStatus {
+                    output_available: self.output.is_some(),
+                    output_error: self.output_error.clone(),
+                    output_device: self.output.as_ref().map(|o| o.device.clone()),
+                    is_playing: self.sink.as_ref().is_some_and(|s| !s.is_paused()) && !self.is_pausing(),
+                    is_paused: self.sink.as_ref().is_some_and(|s| s.is_paused()) || self.is_pausing(),
+                    now_playing: self.now_playing.clone(),
+                    position_ms: self.sink.as_ref().map(|s| s.get_pos().as_millis() as u64),
+                    duration_ms: self.track_duration.map(|d| d.as_millis() as u64),
+                    ambient: self.ambient,
+                });
+            }
+            AudioCommand::Seek(position, reply) => {
+                let _ = reply.send(self.seek(position));
+            }
+            AudioCommand::PlaySound(event) => {
+                let choice = self.sounds.choice_for(event).clone();
+                if let Err(e) = self.play_sound(&choice) {
+                    // A missing or broken custom sound shouldn't silence the cue
+                    eprintln!("Falling back to chime: {}", e);
+                    let _ = self.play_sound(&SoundChoice::Chime);
+                }
+            }
+            AudioCommand::PreviewSound(choice, reply) => {
+                let _ = reply.send(self.play_sound(&choice));
+            }
+            AudioCommand::SetSounds(sounds) => {
+                self.sounds = sounds;
+            }
+            AudioCommand::Announce(announcement) => self.announce(announcement),
+            AudioCommand::SetAnnouncements(announcements) => {
+                self.announcements = announcements;
+            }
+            AudioCommand::Pause => {
+                if self.sink.as_ref().is_some_and(|s| s.is_paused()) || self.is_pausing() {
+                    self.resume();
+                } else {
+                    self.pause_music();
+                }
+            }
+            AudioCommand::PauseIfPlaying => {
+                if !self.sink.as_ref().is_some_and(|s| s.is_paused()) && !self.is_pausing() {
+                    self.pause_music();
+                }
+            }
+            AudioCommand::Stop => self.stop_music(),
+            AudioCommand::Next => {
+                let has_next = self.playlist.as_mut().is_some_and(|p| p.skip_forward().is_some());
+                if has_next {
+                    self.play_current_or_report(self.fades.crossfade());
+                }
+            }
+            AudioCommand::Previous => {
+                let has_previous = self.playlist.as_mut().is_some_and(|p| p.skip_back().is_some());
+                if has_previous {
+                    self.play_current_or_report(self.fades.crossfade());
+                }
+            }
+            AudioCommand::SetRepeat(repeat) => {
+                self.repeat = repeat;
+                if let Some(ref mut p) = self.playlist {
+                    p.set_repeat(repeat);
+                }
+            }
+            AudioCommand::SetShuffle(shuffle) => {
+                self.shuffle = shuffle;
+                if let Some(ref mut p) = self.playlist {
+                    p.set_shuffle(shuffle);
+                }
+            }
+            AudioCommand::SetVolume(vol) => {
+                self.volume = vol;
+                // Retarget a fade-in instead of jumping past it; the next step applies the rest
+                if let Some(ref mut ramp) = self.ramp {
+                    if !ramp.pause_when_done {
+                        ramp.to = vol;
+                    }
+                }
+            }
+            AudioCommand::PlayAmbient(sound) => self.play_ambient(sound),
+            AudioCommand::StopAmbient => self.stop_ambient(),
+            AudioCommand::PlayTaskAudio(audio) => match audio {
+                TaskAudio::Silence => {
+                    self.stop_music();
+                    self.stop_ambient();
+                }
+                TaskAudio::Music { sources } => {
+                    self.stop_ambient();
+                    if self.sink.is_some() && self.sources.as_ref() == Some(&sources) {
+                        self.resume();
+                    } else if let Err(e) = self.play_sources(sources, self.fades.crossfade()) {
+                        self.report_error(e);
+                    }
+                }
+                TaskAudio::Ambient { sound } => {
+                    self.stop_music();
+                    if self.ambient != Some(sound) {
+                        self.play_ambient(sound);
+                    }
+                }
+            },
+            AudioCommand::SetAmbientVolume(vol) => {
+                self.ambient_volume = vol;
+                if let Some(ref s) = self.ambient_sink {
+                    s.set_volume(vol);
+                }
+            }
+            AudioCommand::SetFades(fades) => {
+                self.fades = fades;
+            }
+            AudioCommand::Tick(task_type) => self.tick(task_type),
+            AudioCommand::SetTick(tick) => {
+                self.tick_source = tick_source(&tick.sample);
+                if let Some(ref s) = self.tick_sink {
+                    s.set_volume(tick.volume);
+                }
+                self.tick = tick;
+            }
+            AudioCommand::SetOutputDevice(device, reply) => {
+                // Kept even when the device is missing, so it is picked up once plugged in
+                self.preferred_device = device.clone();
+                let result = match device {
+                    Some(name) if !self.devices.names.contains(&name) => Err(AudioError::DeviceNotFound { name }),
+                    _ => self.connect(),
+                };
+                let _ = reply.send(result);
+            }
+            AudioCommand::PreferOutputDevice(device) => self.preferred_device = device,
+            AudioCommand::DevicesChecked(devices) => {
+                self.devices = devices;
+                self.check_output();
+            }
+        }
+    }
+
+    fn no_output(&self) -> AudioError {
+        AudioError::NoOutputDevice {
+            message: self.output_error.clone().unwrap_or_else(|| "not connected".to_string()),
+        }
+    }
+
+    /// Opens the preferred device, or the default one while it is missing,
+    /// and moves everything that was playing over to it.
+    fn connect(&mut self) -> Result<(), AudioError> {
+        // Only look up a device the watcher has seen; anything else goes straight to the default
+        let preferred = self.preferred_device.as_deref().filter(|name| self.devices.names.iter().any(|n| n == name));
+        match Output::open(preferred) {
+            Ok(output) => {
+                self.move_to(output);
+                Ok(())
+            }
+            Err(message) => {
+                if self.output.as_ref().is_some_and(Output::is_lost) {
+                    self.output = None;
+                }
+                self.output_error = Some(message);
+                Err(self.no_output())
+            }
+        }
+    }
+
+    fn move_to(&mut self, output: Output) {
+        let resume = self.sink.as_ref().map(|s| (s.get_pos(), s.is_paused() || self.is_pausing()));
+
+        // Sinks are tied to the old stream's mixer; rebuild the long-running ones below
+        self.sink = None;
+        self.ramp = None;
+        self.fading_out.clear();
+        self.notification_sinks.clear();
+        self.duck = None;
+        self.tick_sink = None;
+        self.ambient_sink = None;
+        let device = output.device.clone();
+        self.output = Some(output);
+        self.output_error = None;
+
+        if let Some(sound) = self.ambient {
+            self.play_ambient(sound);
+        }
+        if let Some((position, paused)) = resume {
+            if self.play_current(Duration::ZERO).is_ok() {
+                if let Some(ref s) = self.sink {
+                    let _ = s.try_seek(position);
+                    if paused {
+                        s.pause();
+                    }
+                }
+            }
+        }
+        if let Some(ref app) = self.app {
+            events::emit(app, GaweEvent::AudioOutputChanged { device });
+        }
+    }
+
+    /// Reconnects when the device is gone, the preferred one came back, the
+    /// system default changed, or nothing could be opened yet.
+    fn check_output(&mut self) {
+        let devices = &self.devices;
+        let reconnect = match self.output {
+            None => !devices.names.is_empty(),
+            Some(ref o) if o.is_lost() => true,
+            Some(ref o) => match self.preferred_device {
+                Some(ref name) => o.device != *name && devices.names.contains(name),
+                None => devices.default.as_ref().is_some_and(|d| *d != o.device),
+            },
+        };
+        // Retries while disconnected stay quiet; the error is in the status
+        let had_output = self.output.is_some();
+        if reconnect {
+            if let Err(e) = self.connect() {
+                if had_output {
+                    eprintln!("Failed to switch audio output: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Plays one tick if ticking is on for this kind of task.
+    fn tick(&mut self, task_type: TaskType) {
+        if !self.tick.enabled_for.contains(&task_type) {
+            return;
+        }
+        let Some(ref output) = self.output else {
+            return;
+        };
+        let volume = self.tick.volume;
+        let sink = self.tick_sink.get_or_insert_with(|| {
+            let sink = Sink::connect_new(output.stream.mixer());
+            sink.set_volume(volume);
+            sink
+        });
+        // Drop the tick rather than drift if the previous one is still queued
+        if sink.len() <= 1 {
+            sink.append(self.tick_source.clone());
+        }
+    }
+
+    fn play_sources(&mut self, sources: Vec<String>, fade: Duration) -> Result<(), AudioError> {
+        let resolved = match self.app {
+            Some(ref app) => app.state::<LibraryManager>().resolve_sources(&sources),
+            None => sources.clone(),
+        };
+        let tracks = playlist::expand(&resolved);
+        self.playlist = Some(Playlist::new(tracks, self.repeat, self.shuffle));
+        self.sources = Some(sources);
+        self.play_current(fade)
+    }
+
+    /// Fades the track out and pauses it once silent.
+    fn pause_music(&mut self) {
+        if self.sink.is_none() {
+            return;
+        }
+        self.remember_position();
+        let mut ramp = Ramp::new(self.music_level(), 0.0, self.fades.pause());
+        ramp.pause_when_done = true;
+        self.ramp = Some(ramp);
+    }
+
+    /// Fades a paused (or pausing) track back in; no-op if it is already playing.
+    fn resume(&mut self) {
+        let Some(ref s) = self.sink else {
+            return;
+        };
+        if !s.is_paused() && !self.is_pausing() {
+            return;
+        }
+        let from = if s.is_paused() { 0.0 } else { self.music_level() };
+        s.play();
+        self.ramp = Some(Ramp::new(from, self.volume, self.fades.pause()));
+    }
+
+    fn stop_music(&mut self) {
+        self.fade_out_current(self.fades.fade_out());
+        self.playlist = None;
+        self.sources = None;
+        self.now_playing = None;
+    }
+
+    /// Without an output the sound is only remembered, and starts once one connects.
+    fn play_ambient(&mut self, sound: AmbientSound) {
+        if let Some(old_sink) = self.ambient_sink.take() {
+            old_sink.stop();
+        }
+        self.ambient = Some(sound);
+        let Some(ref output) = self.output else {
+            return;
+        };
+        let ambient_sink = Sink::connect_new(output.stream.mixer());
+        ambient_sink.set_volume(self.ambient_volume);
+        ambient_sink.append(sound.source());
+        self.ambient_sink = Some(ambient_sink);
+    }
+
+    fn stop_ambient(&mut self) {
+        if let Some(ref s) = self.ambient_sink {
+            s.stop();
+        }
+        self.ambient_sink = None;
+        self.ambient = None;
+    }
+
+    fn seek(&mut self, position: Duration) -> Result<(), AudioError> {
+        let Some(ref sink) = self.sink else {
+            return Err(AudioError::NotPlaying);
+        };
+        sink.try_seek(position).map_err(|e| AudioError::SeekFailed { message: e.to_string() })?;
+        self.remember_position();
+        Ok(())
+    }
+
+    /// Records where the current track is, forgetting it instead when it is
+    /// close to the start or end, and queues a save if anything changed.
+    fn remember_position(&mut self) {
+        let (Some(ref sink), Some(ref now_playing)) = (&self.sink, &self.now_playing) else {
+            return;
+        };
+        let position = sink.get_pos();
+        let near_end = sink.empty()
+            || self.track_duration.is_some_and(|total| position + RESUME_MARGIN.max(self.fades.crossfade()) >= total);
+        let resume_at = (!near_end && position >= RESUME_MARGIN).then_some(position.as_millis() as u64);
+
+        self.last_position_save = Instant::now();
+        if self.positions.set(&now_playing.path, resume_at) {
+            if let Some(ref saver) = self.position_saver {
+                let _ = saver.send(self.positions.entries.clone());
+            }
+        }
+    }
+
+    /// Periodically saves the position of a track that is playing.
+    fn checkpoint_position(&mut self) {
+        let playing = self.sink.as_ref().is_some_and(|s| !s.is_paused());
+        if playing && self.last_position_save.elapsed() >= POSITION_SAVE_INTERVAL {
+            self.remember_position();
+        }
+    }
+
+    fn is_pausing(&self) -> bool {
+        self.ramp.as_ref().is_some_and(|r| r.pause_when_done)
+    }
+
+    fn is_fading(&self) -> bool {
+        self.ramp.is_some()
+            || !self.fading_out.is_empty()
+            || self.duck.is_some()
+            || !self.notification_sinks.is_empty()
+    }
+
+    /// Music volume before ducking: the fade in progress, or the user's volume.
+    fn music_level(&self) -> f32 {
+        self.ramp.as_ref().map_or(self.volume, Ramp::level)
+    }
+
+    fn duck_gain(&self) -> f32 {
+        self.duck.as_ref().map_or(1.0, Ramp::level)
+    }
+
+    /// Hands the current sink over to the fade-out list, or stops it right
+    /// away when `duration` is zero.
+    fn fade_out_current(&mut self, duration: Duration) {
+        self.remember_position();
+        self.ramp = None;
+        self.track_duration = None;
+        if let Some(old_sink) = self.sink.take() {
+            if duration.is_zero() || old_sink.is_paused() {
+                old_sink.stop();
+            } else {
+                let ramp = Ramp::new(old_sink.volume(), 0.0, duration);
+                self.fading_out.push((old_sink, ramp));
+            }
+        }
+    }
+
+    /// Advances all fades by one step.
+    fn step_fades(&mut self) {
+        // Bring the music back up once every notification has finished
+        self.notification_sinks.retain(|s| !s.empty());
+        if self.notification_sinks.is_empty() {
+            match self.duck {
+                Some(ref duck) if duck.to < 1.0 => {
+                    self.duck = Some(Ramp::new(duck.level(), 1.0, DUCK_RELEASE));
+                }
+                Some(ref duck) if duck.is_done() => self.duck = None,
+                _ => {}
+            }
+        }
+
+        let level = self.music_level() * self.duck_gain();
+        if let Some(ref sink) = self.sink {
+            sink.set_volume(level);
+            if let Some(ref ramp) = self.ramp {
+                if ramp.is_done() {
+                    if ramp.pause_when_done {
+                        sink.pause();
+                    }
+                    self.ramp = None;
+                }
+            }
+        }
+
+        self.fading_out.retain(|(sink, ramp)| {
+            sink.set_volume(ramp.level());
+            if ramp.is_done() {
+                sink.stop();
+            }
+            !ramp.is_done()
+        });
+    }
+
+    /// Starts the playlist's current track, skipping entries that fail to
+    /// open or decode. Gives up after one pass over the list and reports
+    /// the first failure. Any playing track fades out over `fade` while the
+    /// new one fades in.
+    fn play_current(&mut self, fade: Duration) -> Result<(), AudioError> {
+        self.fade_out_current(fade);
+        self.now_playing = None;
+        let Some(ref output) = self.output else {
+            return Err(self.no_output());
+        };
+        let Some(ref mut playlist) = self.playlist else {
+            return Err(AudioError::EmptyPlaylist);
+        };
+
+        let mut first_error = None;
+        for _ in 0..playlist.len() {
+            let Some(path) = playlist.current().map(str::to_owned) else {
+                break;
+            };
+
+            match open_track(&path) {
+                Ok(source) => {
+                    self.track_duration = source.total_duration();
+                    // connect_new returns Sink directly, not Result
+                    let new_sink = Sink::connect_new(output.stream.mixer());
+                    if fade.is_zero() {
+                        new_sink.set_volume(self.volume);
+                    } else {
+                        new_sink.set_volume(0.0);
+                        self.ramp = Some(Ramp::new(0.0, self.volume, fade));
+                    }
+                    new_sink.append(source);
+                    if let Some(ms) = self.positions.get(&path) {
+                        // Still silent here, so the jump isn't audible
+                        if let Err(e) = new_sink.try_seek(Duration::from_millis(ms)) {
+                            eprintln!("Can't resume {} where it left off: {}", path, e);
+                        }
+                    }
+                    self.sink = Some(new_sink);
+
+                    let now_playing = NowPlaying {
+                        title: Path::new(&path)
+                            .file_stem()
+                            .map(|s| s.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| path.clone()),
+                        index: playlist.current_index().unwrap_or(0),
+                        total: playlist.len(),
+                        path,
+                    };
+                    if let Some(ref app) = self.app {
+                        events::emit(app, GaweEvent::NowPlaying(now_playing.clone()));
+                    }
+                    self.now_playing = Some(now_playing);
+                    return Ok(());
+                }
+                Err(e) => {
+                    eprintln!("Skipping unplayable track: {}", e);
+                    first_error.get_or_insert(e);
+                }
+            }
+
+            if playlist.skip_forward().is_none() {
+                break;
+            }
+        }
+        self.playlist = None;
+        Err(first_error.unwrap_or(AudioError::EmptyPlaylist))
+    }
+
+    /// For transitions nobody is waiting on; failures go out as `audio-error`.
+    fn play_current_or_report(&mut self, fade: Duration) {
+        if let Err(e) = self.play_current(fade) {
+            self.report_error(e);
+        }
+    }
+
+    fn report_error(&self, error: AudioError) {
+        if let Some(ref app) = self.app {
+            events::emit(app, GaweEvent::AudioError { error });
+        }
+    }
+
+    /// Moves on to the next track once the sink has drained, or slightly
+    /// before that when a crossfade is configured and the length is known.
+    fn advance_if_finished(&mut self) {
+        let Some(ref sink) = self.sink else {
+            return;
+        };
+        if sink.is_paused() || self.is_pausing() {
+            return;
+        }
+        // At most half the track, so a short one isn't already "near the end"
+        // as soon as it starts and a repeating playlist doesn't race through
+        let crossfade = self
+            .track_duration
+            .map_or(Duration::ZERO, |total| self.fades.crossfade().min(total / 2));
+        let finished = sink.empty();
+        let near_end = !crossfade.is_zero()
+            && self.track_duration.is_some_and(|total| sink.get_pos() + crossfade >= total);
+        if !finished && !near_end {
+            return;
+        }
+
+        let has_next = self.playlist.as_mut().is_some_and(|p| p.on_track_end().is_some());
+        if has_next {
+            self.play_current_or_report(if finished { Duration::ZERO } else { crossfade });
+        } else if finished {
+            self.remember_position();
+            self.sink = None;
+            self.ramp = None;
+            self.playlist = None;
+            self.sources = None;
+            self.now_playing = None;
+        }
+    }
+
+    /// Plays a one-shot notification sound on its own sink at the bell
+    /// volume, ducking the music until it finishes.
+    fn play_sound(&mut self, choice: &SoundChoice) -> Result<(), AudioError> {
+        if *choice == SoundChoice::Silent {
+            return Ok(());
+        }
+        let Some(ref output) = self.output else {
+            return Err(self.no_output());
+        };
+        let bell_sink = Sink::connect_new(output.stream.mixer());
+        bell_sink.set_volume(self.sounds.volume);
+
+        match choice {
+            SoundChoice::Silent => {}
+            SoundChoice::Chime => {
+                let source = SineWave::new(880.0)
+                    .take_duration(Duration::from_secs_f32(0.2))
+                    .amplify(0.5);
+
+                let source2 = SineWave::new(1760.0)
+                    .take_duration(Duration::from_secs_f32(0.2))
+                    .amplify(0.3);
+
+                bell_sink.append(source.mix(source2));
+            }
+            SoundChoice::Bundled { .. } | SoundChoice::File { .. } => {
+                let path = self.app.as_ref()
+                    .and_then(|app| choice.resolve(app))
+                    .ok_or(AudioError::Unavailable)?;
+                bell_sink.append(open_track(&path.to_string_lossy())?);
+            }
+        }
+
+        self.notification_sinks.push(bell_sink);
+        self.duck_music();
+        Ok(())
+    }
+
+    /// Speaks the phrase for `announcement` if it is enabled, after any bell
+    /// that is still ringing.
+    fn announce(&mut self, announcement: Announcement) {
+        if !self.announcements.enabled.contains(&announcement) {
+            return;
+        }
+        let (Some(ref app), Some(ref output)) = (&self.app, &self.output) else {
+            return;
+        };
+        let Some(clips) = announcement.clips(app) else {
+            self.speak(announcement);
+            return;
+        };
+
+        let voice_sink = Sink::connect_new(output.stream.mixer());
+        voice_sink.set_volume(self.announcements.volume);
+        if !self.notification_sinks.is_empty() {
+            voice_sink.append(Zero::new(1, 44_100).take_duration(ANNOUNCE_DELAY));
+        }
+        for clip in clips {
+            match open_track(&clip.to_string_lossy()) {
+                Ok(source) => voice_sink.append(source),
+                Err(e) => {
+                    eprintln!("Can't play voice clip: {}", e);
+                    return;
+                }
+            }
+        }
+
+        self.notification_sinks.push(voice_sink);
+        self.duck_music();
+    }
+
+    /// Falls back to the system speech synthesizer when the voice clips
+    /// aren't bundled, and to the chime when there is none either.
+    fn speak(&mut self, announcement: Announcement) {
+        let delay = if self.notification_sinks.is_empty() { Duration::ZERO } else { ANNOUNCE_DELAY };
+        if !announce::speak(&announcement.phrase(), self.announcements.volume, delay) {
+            let _ = self.play_sound(&SoundChoice::Chime);
+            return;
+        }
+        // The synthesizer plays outside our mixer; a silent sink keeps the music ducked meanwhile
+        let Some(ref output) = self.output else {
+            return;
+        };
+        let words = announcement.words().len() as u32;
+        let speaking = Sink::connect_new(output.stream.mixer());
+        speaking.append(Zero::new(1, 44_100).take_duration(delay + SPEECH_STARTUP + SPOKEN_WORD * words));
+        self.notification_sinks.push(speaking);
+        self.duck_music();
+    }
+
+    fn duck_music(&mut self) {
+        if self.sounds.duck_level < 1.0 {
+            self.duck = Some(Ramp::new(self.duck_gain(), self.sounds.duck_level, DUCK_ATTACK));
+        }
+    }
+}
+
+fn open_track(path: &str) -> Result<Decoder<BufReader<File>>, AudioError> {
+    let file = File::open(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => AudioError::FileNotFound { path: path.to_string() },
+        _ => AudioError::Io { path: path.to_string(), message: e.to_string() },
+    })?;
+    // rodio 0.21 recommends Decoder::try_from for files
+    Decoder::try_from(file).map_err(|_| AudioError::UnsupportedFormat { path: path.to_string() })
+}
+
+/// Builds the tick for `sample`, falling back to the mechanical click when a
+/// file can't be used.
+fn tick_source(sample: &TickSample) -> Buffered<Box<dyn Source + Send>> {
+    if let TickSample::File { path } = sample {
+        match open_track(path) {
+            Ok(decoder) => {
+                let source: Box<dyn Source + Send> = Box::new(decoder.take_duration(MAX_TICK_LENGTH));
+                return source.buffered();
+            }
+            Err(e) => eprintln!("Using the default tick: {}", e),
+        }
+    }
+    let source: Box<dyn Source + Send> = Box::new(sample.click());
+    source.buffered()
 }
 
 pub struct AudioManager {
     sender: Arc<Mutex<mpsc::Sender<AudioCommand>>>,
 }
 
+impl Default for AudioManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AudioManager {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::channel();
 
         thread::spawn(move || {
             // Initialize audio output stream in this thread
-            let device_result = OutputStreamBuilder::open_default_stream();
-            
-            if let Ok(stream) = device_result {
-                let mut sink: Option<Sink> = None;
-                let mut current_volume = 0.5;
-
-                while let Ok(command) = rx.recv() {
-                    match command {
-                        AudioCommand::Play(path) => {
-                            // Stop existing
-                            if let Some(old_sink) = sink.take() {
-                                old_sink.stop();
-                            }
-
-                            if let Ok(file) = File::open(&path) {
-                                // rodio 0.21 recommends Decoder::try_from for files
-                                if let Ok(source) = Decoder::try_from(file) {
-                                    // connect_new returns Sink directly, not Result
-                                    let new_sink = Sink::connect_new(&stream.mixer());
-                                    new_sink.set_volume(current_volume);
-                                    new_sink.append(source);
-                                    sink = Some(new_sink);
-                                }
-                            }
-                        }
-                        AudioCommand::PlayBell => {
-                            // Play bell sound from resource file
-                            // Try to find the bell file relative to the executable (better for packaged apps)
-                            let mut bell_path_buf = std::env::current_exe().map(|mut p| {
-                                p.pop(); // Remove executable name
-                                if p.ends_with("MacOS") {
-                                    p.pop(); // Remove MacOS
-                                    p.push("Resources"); // Go to Resources in .app bundle
-                                }
-                                p
-                            }).unwrap_or_else(|_| std::env::current_dir().unwrap_or_default());
-                            
-                            // If we are in dev mode (cargo run), we might be in src-tauri or project root
-                            // Check if "dist" exists here, if not, try to go up one level
-                            if !bell_path_buf.join("dist").exists() {
-                                if let Ok(cwd) = std::env::current_dir() {
-                                    bell_path_buf = cwd;
-                                }
-                            }
-                            
-                            bell_path_buf.push("dist");
-                            bell_path_buf.push("tone");
-                            bell_path_buf.push("bell.mp3");
-
-                            let mut played_file = false;
-                            
-                            if let Some(bell_path) = bell_path_buf.to_str() {
-                                if let Ok(file) = File::open(bell_path) {
-                                    if let Ok(source) = Decoder::try_from(file) {
-                                        // Create a fresh sink for file playback
-                                        let bell_sink = Sink::connect_new(&stream.mixer());
-                                        bell_sink.set_volume(0.8);
-                                        bell_sink.append(source);
-                                        bell_sink.detach();
-                                        played_file = true;
-                                    }
-                                }
-                            }
-
-                            if !played_file {
-                                // Fallback to SineWave
-                                // Create a fresh sink for fallback playback
-                                let bell_sink = Sink::connect_new(&stream.mixer());
-
-                                let source = SineWave::new(880.0)
-                                    .take_duration(Duration::from_secs_f32(0.2))
-                                    .amplify(0.5);
-                                    
-                                let source2 = SineWave::new(1760.0)
-                                    .take_duration(Duration::from_secs_f32(0.2))
-                                    .amplify(0.3);
-
-                                bell_sink.set_volume(0.8);
-                                bell_sink.append(source.mix(source2));
-                                bell_sink.detach();
-                            }
-                        }
-                        AudioCommand::Pause => {
-                            if let Some(ref s) = sink {
-                                if s.is_paused() {
-                                    s.play();
-                                } else {
-                                    s.pause();
-                                }
-                            }
-                        }
-                        AudioCommand::Stop => {
-                            if let Some(ref s) = sink {
-                                s.stop();
-                            }
-                            sink = None;
-                        }
-                        AudioCommand::SetVolume(vol) => {
-                            current_volume = vol;
-                            if let Some(ref s) = sink {
-                                s.set_volume(vol);
-                            }
-                        }
-                    }
+            let mut player = Player::new();
+            if let Err(e) = player.connect() {
+                eprintln!("Failed to initialize audio output stream: {}", e);
+            }
+
+            loop {
+                let wait = if player.is_fading() { FADE_STEP_INTERVAL } else { TRACK_POLL_INTERVAL };
+                match rx.recv_timeout(wait) {
+                    Ok(command) => player.handle(command),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
                 }
-            } else {
-                eprintln!("Failed to initialize audio output stream");
-                // Drain channel
-                while let Ok(_) = rx.recv() {} 
+                player.step_fades();
+                player.advance_if_finished();
+                player.checkpoint_position();
             }
         });
 
-        Self {
-            sender: Arc::new(Mutex::new(tx)),
+        let sender = Arc::new(Mutex::new(tx));
+
+        // Holds only a weak handle so dropping the manager still closes the channel
+        let watcher = Arc::downgrade(&sender);
+        thread::spawn(move || loop {
+            let snapshot = DeviceSnapshot::take();
+            let Some(sender) = watcher.upgrade() else {
+                break;
+            };
+            if sender.lock().unwrap().send(AudioCommand::DevicesChecked(snapshot)).is_err() {
+                break;
+            }
+            drop(sender);
+            thread::sleep(DEVICE_CHECK_INTERVAL);
+        });
+
+        Self { sender }
+    }
+
+    /// Lets the audio thread emit `now-playing` events and use the settings store.
+    pub fn attach_app(&self, app: AppHandle) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::AttachApp(app));
+    }
+
+    /// Sends a command that expects an answer and waits for it.
+    fn request<T>(&self, command: impl FnOnce(mpsc::Sender<T>) -> AudioCommand) -> Result<T, AudioError> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.sender.lock().unwrap().send(command(reply_tx)).map_err(|_| AudioError::Unavailable)?;
+        reply_rx.recv_timeout(REPLY_TIMEOUT).map_err(|_| AudioError::Unavailable)
+    }
+
+    pub fn play(&self, path: String) -> Result<(), AudioError> {
+        self.play_playlist(vec![path])
+    }
+
+    /// Plays files, folders and M3U/PLS playlists as one queue. Returns once
+    /// the first playable track has started.
+    pub fn play_playlist(&self, sources: Vec<String>) -> Result<(), AudioError> {
+        self.request(|reply| AudioCommand::Play(sources, reply))?
+    }
+
+    /// Switches playback to `device`, or back to the system default with `None`.
+    pub fn set_output_device(&self, device: Option<String>) -> Result<(), AudioError> {
+        self.request(|reply| AudioCommand::SetOutputDevice(device, reply))?
+    }
+
+    /// Picks up the device saved last time without blocking; playback moves
+    /// to it once the device watcher reports it.
+    pub fn restore_output_device(&self, device: Option<String>) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::PreferOutputDevice(device));
+    }
+
+    /// Switches, and remembers the choice for the next launch.
+    pub fn update_output_device(&self, app: &AppHandle, device: Option<String>) -> Result<(), AudioError> {
+        if let Err(e) = settings::save(app, settings::OUTPUT_DEVICE_KEY, &device) {
+            eprintln!("Failed to save output device: {}", e);
         }
+        self.set_output_device(device)
+    }
+
+    /// Jumps within the current track; the new spot is remembered for next time.
+    pub fn seek(&self, position: Duration) -> Result<(), AudioError> {
+        self.request(|reply| AudioCommand::Seek(position, reply))?
+    }
+
+    pub fn status(&self) -> AudioStatus {
+        self.request(AudioCommand::Status).unwrap_or(AudioStatus {
+            output_available: false,
+            output_error: Some(AudioError::Unavailable.to_string()),
+            output_device: None,
+            is_playing: false,
+            is_paused: false,
+            now_playing: None,
+            position_ms: None,
+            duration_ms: None,
+            ambient: None,
+        })
+    }
+
+    pub fn play_sound(&self, event: SoundEvent) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::PlaySound(event));
+    }
+
+    /// Plays `choice` once so the user can hear it before saving.
+    pub fn preview_sound(&self, choice: SoundChoice) -> Result<(), AudioError> {
+        self.request(|reply| AudioCommand::PreviewSound(choice, reply))?
+    }
+
+    pub fn announce(&self, announcement: Announcement) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::Announce(announcement));
+    }
+
+    pub fn set_announcements(&self, announcements: AnnouncementSettings) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetAnnouncements(announcements));
     }
 
-    pub fn play(&self, path: String) -> Result<(), String> {
-        self.sender.lock().unwrap().send(AudioCommand::Play(path)).map_err(|e| e.to_string())
+    /// Applies, persists and broadcasts which announcements are spoken.
+    pub fn update_announcements(&self, app: &AppHandle, announcements: AnnouncementSettings) -> Result<(), GaweError> {
+        self.set_announcements(announcements.clone());
+        settings::save(app, settings::ANNOUNCEMENTS_KEY, &announcements)?;
+        events::emit(app, GaweEvent::AnnouncementSettingsChanged(announcements));
+        Ok(())
     }
 
-    pub fn play_bell(&self) {
-        let _ = self.sender.lock().unwrap().send(AudioCommand::PlayBell);
+    pub fn set_sounds(&self, sounds: SoundSettings) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetSounds(sounds));
     }
 
+    /// Applies, persists and broadcasts new sound choices.
+    pub fn update_sounds(&self, app: &AppHandle, sounds: SoundSettings) -> Result<(), GaweError> {
+        self.set_sounds(sounds.clone());
+        settings::save(app, settings::SOUNDS_KEY, &sounds)?;
+        events::emit(app, GaweEvent::SoundSettingsChanged(sounds));
+        Ok(())
+    }
+
+    /// Toggles between paused and playing.
     pub fn pause(&self) {
         let _ = self.sender.lock().unwrap().send(AudioCommand::Pause);
     }
 
+    pub fn pause_if_playing(&self) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::PauseIfPlaying);
+    }
+
     pub fn stop(&self) {
         let _ = self.sender.lock().unwrap().send(AudioCommand::Stop);
     }
 
+    pub fn next(&self) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::Next);
+    }
+
+    pub fn previous(&self) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::Previous);
+    }
+
+    pub fn set_repeat(&self, repeat: RepeatMode) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetRepeat(repeat));
+    }
+
+    pub fn set_shuffle(&self, shuffle: bool) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetShuffle(shuffle));
+    }
+
     pub fn set_volume(&self, volume: f32) {
         let _ = self.sender.lock().unwrap().send(AudioCommand::SetVolume(volume));
     }
+
+    pub fn set_fades(&self, fades: FadeSettings) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetFades(fades));
+    }
+
+    /// Applies, persists and broadcasts new fade durations.
+    pub fn update_fades(&self, app: &AppHandle, fades: FadeSettings) -> Result<(), GaweError> {
+        self.set_fades(fades.clone());
+        settings::save(app, settings::FADES_KEY, &fades)?;
+        events::emit(app, GaweEvent::FadeSettingsChanged(fades));
+        Ok(())
+    }
+
+    /// Called on every second of the timer while a task runs.
+    pub fn tick(&self, task_type: TaskType) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::Tick(task_type));
+    }
+
+    pub fn set_tick(&self, tick: TickSettings) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetTick(tick));
+    }
+
+    /// Applies, persists and broadcasts new tick settings.
+    pub fn update_tick(&self, app: &AppHandle, tick: TickSettings) -> Result<(), GaweError> {
+        self.set_tick(tick.clone());
+        settings::save(app, settings::TICK_KEY, &tick)?;
+        events::emit(app, GaweEvent::TickSettingsChanged(tick));
+        Ok(())
+    }
+
+    pub fn play_ambient(&self, sound: AmbientSound) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::PlayAmbient(sound));
+    }
+
+    pub fn stop_ambient(&self) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::StopAmbient);
+    }
+
+    /// Switches to what a task asked for; failures are reported as `audio-error` events.
+    pub fn play_task_audio(&self, audio: TaskAudio) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::PlayTaskAudio(audio));
+    }
+
+    pub fn set_ambient_volume(&self, volume: f32) {
+        let _ = self.sender.lock().unwrap().send(AudioCommand::SetAmbientVolume(volume));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_report_only_real_changes() {
+        let mut positions = Positions::default();
+        assert!(positions.set("a.mp3", Some(60_000)));
+        assert!(!positions.set("a.mp3", Some(60_000)));
+        assert!(positions.set("a.mp3", None));
+        assert!(!positions.set("a.mp3", None));
+        assert_eq!(positions.get("a.mp3"), None);
+    }
+
+    #[test]
+    fn positions_forget_least_recently_played() {
+        let mut positions = Positions::default();
+        for i in 0..MAX_POSITIONS {
+            positions.set(&format!("{}.mp3", i), Some(10_000));
+        }
+        // Playing the oldest again keeps it around
+        positions.set("0.mp3", Some(20_000));
+        positions.set("new.mp3", Some(10_000));
+
+        assert_eq!(positions.entries.len(), MAX_POSITIONS);
+        assert_eq!(positions.get("0.mp3"), Some(20_000));
+        assert_eq!(positions.get("1.mp3"), None);
+        assert_eq!(positions.entries.last().unwrap().0, "new.mp3");
+    }
 }
@@ -0,0 +1,228 @@
+use crate::ipc::IpcRequest;
+use crate::statusbar::{self, BarFormat};
+use crate::timer::{self, TimerUpdate};
+
+const USAGE: &str = "\
+Usage: gawe <command> [--json]
+
+Commands:
+  start [session]   Start a saved session by name or id, or resume the loaded one
+  pause             Pause the timer
+  skip              Skip to the next task
+  status            Show the current task and time left
+  bar <format>      Print a status-bar line: waybar, polybar or i3blocks
+  events            Print every app event as a line of JSON until GaWe exits
+
+Options:
+  --json            Print the timer status as JSON
+  --follow          With bar, print a new line every second";
+
+/// A parsed command line.
+#[derive(Debug, PartialEq)]
+enum Invocation {
+    Send { request: IpcRequest, json: bool },
+    Bar { format: BarFormat, follow: bool },
+    Events,
+    Help,
+}
+
+/// Runs a subcommand against the running app and returns the exit code, or
+/// `None` when `args` don't name one and the app itself should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    let code = match parse(args)? {
+        Ok(Invocation::Send { request, json }) => send(&request, json),
+        Ok(Invocation::Bar { format, follow }) => bar(format, follow),
+        Ok(Invocation::Events) => events(),
+        Ok(Invocation::Help) => {
+            println!("{}", USAGE);
+            0
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            2
+        }
+    };
+    Some(code)
+}
+
+/// `None` when `args` should go to the app; `Err` explains a bad command line.
+fn parse(args: &[String]) -> Option<Result<Invocation, String>> {
+    // Anything flag-like or a gawe:// link is left to the app, e.g. what a desktop launcher passes
+    let command = args
+        .first()
+        .filter(|arg| (!arg.starts_with('-') && !arg.contains("://")) || *arg == "--help")?;
+    let json = args.iter().any(|arg| arg == "--json");
+    let follow = args.iter().any(|arg| arg == "--follow");
+    let rest: Vec<&str> = args[1..]
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--json" && *arg != "--follow")
+        .collect();
+
+    let request = match command.as_str() {
+        "start" if rest.is_empty() => IpcRequest::Start { session: None },
+        "start" => IpcRequest::Start { session: Some(rest.join(" ")) },
+        "pause" => IpcRequest::Pause,
+        "skip" => IpcRequest::Skip,
+        "status" => IpcRequest::Status,
+        "bar" => {
+            return Some(match rest.first().and_then(|name| BarFormat::parse(name)) {
+                Some(format) => Ok(Invocation::Bar { format, follow }),
+                None => Err("Expected a bar format: waybar, polybar or i3blocks".to_string()),
+            })
+        }
+        "events" => return Some(Ok(Invocation::Events)),
+        "help" | "--help" => return Some(Ok(Invocation::Help)),
+        other => return Some(Err(format!("Unknown command: {}", other))),
+    };
+    Some(Ok(Invocation::Send { request, json }))
+}
+
+#[cfg(unix)]
+fn send(request: &IpcRequest, json: bool) -> i32 {
+    match crate::ipc::send(request) {
+        Ok(response) => {
+            if json {
+                println!("{}", serde_json::to_string(&response.status).unwrap_or_default());
+            } else if matches!(request, IpcRequest::Status) || response.error.is_none() {
+                println!("{}", describe(response.status.as_ref()));
+            }
+            match response.error {
+                Some(error) => {
+                    eprintln!("{}", error);
+                    1
+                }
+                None => 0,
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Prints the status for a bar; a GaWe that isn't running shows as idle
+/// rather than an error so the bar just hides the block.
+#[cfg(unix)]
+fn bar(format: BarFormat, follow: bool) -> i32 {
+    loop {
+        let status = crate::ipc::send(&IpcRequest::Status).ok().and_then(|r| r.status);
+        println!("{}", statusbar::render(format, status.as_ref()));
+        if !follow {
+            return 0;
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+}
+
+#[cfg(unix)]
+fn events() -> i32 {
+    let result = crate::ipc::subscribe(|line| println!("{}", line));
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn events() -> i32 {
+    eprintln!("Event streaming is only available on Unix systems");
+    1
+}
+
+#[cfg(not(unix))]
+fn bar(_format: BarFormat, _follow: bool) -> i32 {
+    eprintln!("Status-bar output is only available on Unix systems");
+    1
+}
+
+#[cfg(not(unix))]
+fn send(_request: &IpcRequest, _json: bool) -> i32 {
+    eprintln!("The command-line interface is only available on Unix systems");
+    1
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn describe(status: Option<&TimerUpdate>) -> String {
+    match status {
+        Some(s) => format!(
+            "{} · {} — {} {}",
+            if s.is_break { "Break" } else { "Focus" },
+            s.current_task_name,
+            timer::format_remaining(s.remaining_seconds),
+            if s.is_running { "left" } else { "left (paused)" },
+        ),
+        None => "No session loaded".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Option<Result<Invocation, String>> {
+        parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    fn request(args: &[&str]) -> IpcRequest {
+        match parse_args(args) {
+            Some(Ok(Invocation::Send { request, .. })) => request,
+            other => panic!("expected a request, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn launcher_arguments_start_the_app() {
+        assert_eq!(parse_args(&[]), None);
+        assert_eq!(parse_args(&["--start", "deep-work"]), None);
+        assert_eq!(parse_args(&["gawe://start?session=Deep%20Work"]), None);
+    }
+
+    #[test]
+    fn start_joins_the_session_name() {
+        assert_eq!(request(&["start"]), IpcRequest::Start { session: None });
+        assert_eq!(
+            request(&["start", "Deep", "Work"]),
+            IpcRequest::Start { session: Some("Deep Work".to_string()) },
+        );
+    }
+
+    #[test]
+    fn simple_commands() {
+        assert_eq!(request(&["pause"]), IpcRequest::Pause);
+        assert_eq!(request(&["skip"]), IpcRequest::Skip);
+        assert_eq!(request(&["status"]), IpcRequest::Status);
+        assert_eq!(parse_args(&["events"]), Some(Ok(Invocation::Events)));
+        assert_eq!(parse_args(&["--help"]), Some(Ok(Invocation::Help)));
+    }
+
+    #[test]
+    fn json_flag_can_go_anywhere() {
+        assert_eq!(
+            parse_args(&["start", "--json", "Deep", "Work"]),
+            Some(Ok(Invocation::Send {
+                request: IpcRequest::Start { session: Some("Deep Work".to_string()) },
+                json: true,
+            })),
+        );
+    }
+
+    #[test]
+    fn bar_needs_a_known_format() {
+        assert_eq!(
+            parse_args(&["bar", "waybar", "--follow"]),
+            Some(Ok(Invocation::Bar { format: BarFormat::Waybar, follow: true })),
+        );
+        assert!(matches!(parse_args(&["bar"]), Some(Err(_))));
+        assert!(matches!(parse_args(&["bar", "lemonbar"]), Some(Err(_))));
+    }
+
+    #[test]
+    fn unknown_commands_are_errors() {
+        assert!(matches!(parse_args(&["restart"]), Some(Err(_))));
+    }
+}
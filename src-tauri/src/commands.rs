@@ -1,51 +1,333 @@
-use tauri::{AppHandle, State};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use crate::error::GaweError;
+use crate::events::{self, EventBus, EventEnvelope, GaweEvent};
 use crate::models::Session;
-use crate::timer::TimerManager;
+use crate::timer::{TimerManager, TimerUpdate};
 use crate::activity::ActivityManager;
-use crate::audio::AudioManager;
+use crate::ambient::AmbientSound;
+use crate::announce::Announcement;
+use crate::audio::{AudioError, AudioManager, AudioStatus};
+use crate::playlist::RepeatMode;
+use crate::library::{LibraryManager, Track};
+use crate::mini;
+use crate::output::{self, OutputDevice};
+use crate::distraction::{AppRules, DistractionManager, TaskFocusSummary};
+use crate::hotkeys::{HotkeyConflict, HotkeyManager};
+use crate::settings::{self, ActivitySettings, AnnouncementSettings, CloseBehavior, DistractionSettings, FadeSettings, HotkeySettings, SoundSettings, TickSettings};
+use crate::sounds::{self, SoundChoice};
 
 #[tauri::command]
-pub fn start_timer(app: AppHandle, state: State<'_, TimerManager>) {
-    state.start(app);
+pub fn start_timer(app: AppHandle, state: State<'_, TimerManager>) -> Result<(), GaweError> {
+    state.start(app)
 }
 
 #[tauri::command]
-pub fn pause_timer(state: State<'_, TimerManager>) {
+pub fn pause_timer(app: AppHandle, state: State<'_, TimerManager>) -> Result<(), GaweError> {
+    let status = state.get_status().ok_or(GaweError::NoSessionLoaded)?;
     state.pause();
+    // The tick loop has stopped, so other windows (e.g. the mini timer) hear it from here
+    events::emit(&app, GaweEvent::TimerUpdate(TimerUpdate { is_running: false, ..status }));
+    Ok(())
 }
 
 #[tauri::command]
-pub fn load_session(session: Session, state: State<'_, TimerManager>) {
+pub fn load_session(app: AppHandle, session: Session, state: State<'_, TimerManager>) -> Result<(), GaweError> {
+    session.validate()?;
+    if let Err(e) = settings::remember_recent_session(&app, &session.id) {
+        eprintln!("Failed to update recent sessions: {}", e);
+    }
     state.load_session(session);
+    Ok(())
 }
 
+/// Loads a saved session by id and starts it; the frontend follows via `session-started`.
 #[tauri::command]
-pub fn get_timer_status(state: State<'_, TimerManager>) -> Option<crate::timer::TimerUpdate> {
-    state.get_status()
+pub fn start_session(app: AppHandle, session_id: String, state: State<'_, TimerManager>) -> Result<(), GaweError> {
+    let session = settings::load_sessions(&app)
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| GaweError::SessionNotFound { session: session_id.clone() })?;
+    session.validate()?;
+    if let Err(e) = settings::remember_recent_session(&app, &session.id) {
+        eprintln!("Failed to update recent sessions: {}", e);
+    }
+    state.load_session(session);
+    state.start(app.clone())?;
+    events::emit(&app, GaweEvent::SessionStarted { session_id });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn skip_task(app: AppHandle, state: State<'_, TimerManager>) -> Result<(), GaweError> {
+    state.skip(&app)
+}
+
+#[tauri::command]
+pub fn get_close_behavior(app: AppHandle) -> Result<CloseBehavior, GaweError> {
+    Ok(settings::load(&app, settings::CLOSE_BEHAVIOR_KEY).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_close_behavior(app: AppHandle, behavior: CloseBehavior) -> Result<(), GaweError> {
+    settings::save(&app, settings::CLOSE_BEHAVIOR_KEY, &behavior)
+}
+
+#[tauri::command]
+pub fn get_timer_status(state: State<'_, TimerManager>) -> Result<Option<TimerUpdate>, GaweError> {
+    Ok(state.get_status())
+}
+
+#[tauri::command]
+pub fn set_activity_monitoring(app: AppHandle, enabled: bool, threshold: u64, state: State<'_, ActivityManager>) -> Result<(), GaweError> {
+    state.update_settings(&app, ActivitySettings { enabled, threshold_seconds: threshold })
+}
+
+#[tauri::command]
+pub fn get_activity_settings(state: State<'_, ActivityManager>) -> Result<ActivitySettings, GaweError> {
+    Ok(state.settings())
+}
+
+#[tauri::command]
+pub fn set_distraction_tracking(app: AppHandle, enabled: bool, state: State<'_, DistractionManager>) -> Result<(), GaweError> {
+    state.update_settings(&app, DistractionSettings { enabled, ..state.settings() })
+}
+
+#[tauri::command]
+pub fn set_app_rules(app: AppHandle, rules: AppRules, state: State<'_, DistractionManager>) -> Result<(), GaweError> {
+    state.update_settings(&app, DistractionSettings { rules, ..state.settings() })
+}
+
+#[tauri::command]
+pub fn get_distraction_settings(state: State<'_, DistractionManager>) -> Result<DistractionSettings, GaweError> {
+    Ok(state.settings())
+}
+
+#[tauri::command]
+pub fn get_focus_summaries(state: State<'_, DistractionManager>) -> Result<Vec<TaskFocusSummary>, GaweError> {
+    Ok(state.get_summaries())
+}
+
+/// Recent events other than timer ticks, oldest first.
+#[tauri::command]
+pub fn get_event_history(state: State<'_, EventBus>) -> Result<Vec<EventEnvelope>, GaweError> {
+    Ok(state.history())
 }
 
 #[tauri::command]
-pub fn set_activity_monitoring(enabled: bool, threshold: u64, state: State<'_, ActivityManager>) {
-    state.set_enabled(enabled);
-    state.set_threshold(threshold);
+pub fn log_interruption(app: AppHandle, state: State<'_, DistractionManager>) -> Result<(), GaweError> {
+    if state.log_interruption(&app) {
+        Ok(())
+    } else {
+        Err(GaweError::NotRunning)
+    }
 }
 
 #[tauri::command]
-pub fn play_music(file_path: String, state: State<'_, AudioManager>) -> Result<(), String> {
-    state.play(file_path)
+pub fn toggle_mini_timer(app: AppHandle) -> Result<(), GaweError> {
+    mini::toggle(&app).map_err(|e| GaweError::Window { message: e.to_string() })
 }
 
 #[tauri::command]
-pub fn pause_music(state: State<'_, AudioManager>) {
+pub fn get_hotkey_settings(app: AppHandle) -> Result<HotkeySettings, GaweError> {
+    Ok(settings::load_hotkeys(&app))
+}
+
+/// Saves the bindings and returns any that couldn't be registered.
+#[tauri::command]
+pub fn set_hotkey_settings(app: AppHandle, hotkeys: HotkeySettings, state: State<'_, HotkeyManager>) -> Result<Vec<HotkeyConflict>, GaweError> {
+    state.update(&app, hotkeys)
+}
+
+#[tauri::command]
+pub fn get_hotkey_conflicts(state: State<'_, HotkeyManager>) -> Result<Vec<HotkeyConflict>, GaweError> {
+    Ok(state.get_conflicts())
+}
+
+#[tauri::command]
+pub fn list_tracks(state: State<'_, LibraryManager>) -> Result<Vec<Track>, GaweError> {
+    Ok(state.list_tracks())
+}
+
+#[tauri::command]
+pub fn search_tracks(query: String, state: State<'_, LibraryManager>) -> Result<Vec<Track>, GaweError> {
+    Ok(state.search(&query))
+}
+
+#[tauri::command]
+pub fn get_library_folders(state: State<'_, LibraryManager>) -> Result<Vec<String>, GaweError> {
+    Ok(state.folders())
+}
+
+#[tauri::command]
+pub fn set_library_folders(app: AppHandle, folders: Vec<String>, state: State<'_, LibraryManager>) -> Result<(), GaweError> {
+    state.set_folders(&app, folders)
+}
+
+#[tauri::command]
+pub fn rescan_library(app: AppHandle, state: State<'_, LibraryManager>) -> Result<(), GaweError> {
+    state.rescan(app);
+    Ok(())
+}
+
+/// Runs an audio request that waits for the audio thread's reply on a
+/// blocking worker, so a busy audio thread can't freeze the window.
+async fn wait_for_audio<T: Send + 'static>(
+    app: AppHandle,
+    request: impl FnOnce(&AppHandle, &AudioManager) -> Result<T, AudioError> + Send + 'static,
+) -> Result<T, GaweError> {
+    tauri::async_runtime::spawn_blocking(move || request(&app, &app.state::<AudioManager>()))
+        .await
+        .map_err(|_| AudioError::Unavailable)?
+        .map_err(GaweError::from)
+}
+
+#[tauri::command]
+pub async fn play_music(app: AppHandle, file_path: String) -> Result<(), GaweError> {
+    wait_for_audio(app, move |_, audio| audio.play(file_path)).await
+}
+
+#[tauri::command]
+pub async fn play_playlist(app: AppHandle, sources: Vec<String>) -> Result<(), GaweError> {
+    wait_for_audio(app, move |_, audio| audio.play_playlist(sources)).await
+}
+
+#[tauri::command]
+pub fn list_output_devices() -> Result<Vec<OutputDevice>, GaweError> {
+    Ok(output::list_devices())
+}
+
+#[tauri::command]
+pub async fn set_output_device(app: AppHandle, device: Option<String>) -> Result<(), GaweError> {
+    wait_for_audio(app, move |app, audio| audio.update_output_device(app, device)).await
+}
+
+#[tauri::command]
+pub async fn seek_music(app: AppHandle, position_ms: u64) -> Result<(), GaweError> {
+    wait_for_audio(app, move |_, audio| audio.seek(Duration::from_millis(position_ms))).await
+}
+
+#[tauri::command]
+pub async fn audio_status(app: AppHandle) -> Result<AudioStatus, GaweError> {
+    wait_for_audio(app, |_, audio| Ok(audio.status())).await
+}
+
+#[tauri::command]
+pub fn next_track(state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.next();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn previous_track(state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.previous();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_repeat_mode(mode: RepeatMode, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.set_repeat(mode);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_shuffle(enabled: bool, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.set_shuffle(enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn pause_music(state: State<'_, AudioManager>) -> Result<(), GaweError> {
     state.pause();
+    Ok(())
 }
 
 #[tauri::command]
-pub fn stop_music(state: State<'_, AudioManager>) {
+pub fn stop_music(state: State<'_, AudioManager>) -> Result<(), GaweError> {
     state.stop();
+    Ok(())
 }
 
 #[tauri::command]
-pub fn set_volume(volume: f32, state: State<'_, AudioManager>) {
+pub fn set_volume(volume: f32, state: State<'_, AudioManager>) -> Result<(), GaweError> {
     state.set_volume(volume);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_fade_settings(app: AppHandle) -> Result<FadeSettings, GaweError> {
+    Ok(settings::load(&app, settings::FADES_KEY).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_fade_settings(app: AppHandle, fades: FadeSettings, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.update_fades(&app, fades)
+}
+
+#[tauri::command]
+pub fn get_sound_settings(app: AppHandle) -> Result<SoundSettings, GaweError> {
+    Ok(settings::load(&app, settings::SOUNDS_KEY).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_sound_settings(app: AppHandle, sounds: SoundSettings, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.update_sounds(&app, sounds)
+}
+
+#[tauri::command]
+pub fn list_announcements() -> Result<Vec<Announcement>, GaweError> {
+    Ok(Announcement::ALL.to_vec())
+}
+
+#[tauri::command]
+pub fn get_announcement_settings(app: AppHandle) -> Result<AnnouncementSettings, GaweError> {
+    Ok(settings::load(&app, settings::ANNOUNCEMENTS_KEY).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_announcement_settings(app: AppHandle, announcements: AnnouncementSettings, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.update_announcements(&app, announcements)
+}
+
+#[tauri::command]
+pub fn get_tick_settings(app: AppHandle) -> Result<TickSettings, GaweError> {
+    Ok(settings::load(&app, settings::TICK_KEY).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_tick_settings(app: AppHandle, tick: TickSettings, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.update_tick(&app, tick)
+}
+
+#[tauri::command]
+pub async fn preview_sound(app: AppHandle, sound: SoundChoice) -> Result<(), GaweError> {
+    wait_for_audio(app, move |_, audio| audio.preview_sound(sound)).await
+}
+
+#[tauri::command]
+pub fn list_bundled_sounds(app: AppHandle) -> Result<Vec<String>, GaweError> {
+    Ok(sounds::list_bundled(&app))
+}
+
+#[tauri::command]
+pub fn list_ambient_sounds() -> Result<Vec<AmbientSound>, GaweError> {
+    Ok(AmbientSound::ALL.to_vec())
+}
+
+#[tauri::command]
+pub fn play_ambient(sound: AmbientSound, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.play_ambient(sound);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_ambient(state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.stop_ambient();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_ambient_volume(volume: f32, state: State<'_, AudioManager>) -> Result<(), GaweError> {
+    state.set_ambient_volume(volume);
+    Ok(())
 }
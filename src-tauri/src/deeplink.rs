@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use crate::commands;
+use crate::events::{self, GaweEvent};
+use crate::models::{Session, Task, TaskType};
+use crate::settings;
+use crate::timer::TimerManager;
+use crate::tray;
+
+pub const SCHEME: &str = "gawe";
+
+const MAX_MINUTES: u32 = 180;
+const MAX_ROUNDS: u32 = 12;
+const MAX_NAME_LENGTH: usize = 60;
+
+/// What a `gawe://` link asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `gawe://start?session=<id or name>` starts a saved session.
+    Start { session: String },
+    /// `gawe://new?work=50&break=10&rounds=4&name=...` builds a one-off session.
+    New {
+        name: String,
+        work_minutes: u32,
+        break_minutes: u32,
+        rounds: u32,
+    },
+}
+
+/// Checks a link before anything runs; links come from wikis and invites, so
+/// anything out of range is refused rather than clamped.
+pub fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not a {}:// link", SCHEME));
+    }
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let number = |key: &str, default: u32, max: u32| -> Result<u32, String> {
+        match query.get(key) {
+            None => Ok(default),
+            Some(value) => value
+                .parse::<u32>()
+                .ok()
+                .filter(|n| (1..=max).contains(n))
+                .ok_or_else(|| format!("\"{}\" must be a number from 1 to {}", key, max)),
+        }
+    };
+
+    match url.host_str() {
+        Some("start") => {
+            let session = query
+                .get("session")
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .ok_or("The link doesn't say which session to start")?;
+            Ok(DeepLink::Start { session: session.to_string() })
+        }
+        Some("new") => Ok(DeepLink::New {
+            name: query
+                .get("name")
+                .map(|name| name.trim().chars().take(MAX_NAME_LENGTH).collect::<String>())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "Session from Link".to_string()),
+            work_minutes: number("work", 25, MAX_MINUTES)?,
+            break_minutes: number("break", 5, MAX_MINUTES)?,
+            rounds: number("rounds", 4, MAX_ROUNDS)?,
+        }),
+        _ => Err(format!("Unknown link: {}", url)),
+    }
+}
+
+/// Registers the scheme where that happens at runtime and handles links
+/// that opened the app as well as those arriving while it runs.
+pub fn start_listening(app: &AppHandle) {
+    let deep_link = app.deep_link();
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = deep_link.register_all() {
+        eprintln!("Failed to register {}:// links: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    deep_link.on_open_url(move |event| handle_urls(&handle, event.urls()));
+    if let Ok(Some(urls)) = deep_link.get_current() {
+        handle_urls(app, urls);
+    }
+}
+
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        match parse(&url) {
+            Ok(link) => open(app, link),
+            Err(e) => {
+                eprintln!("Ignoring link {}: {}", url, e);
+                app.dialog()
+                    .message(e)
+                    .title("Invalid GaWe Link")
+                    .kind(MessageDialogKind::Error)
+                    .show(|_| {});
+            }
+        }
+    }
+}
+
+fn open(app: &AppHandle, link: DeepLink) {
+    let replacing = app.state::<TimerManager>().get_status().is_some_and(|s| s.is_running);
+    let replace_note = if replacing { "\n\nThis replaces the session in progress." } else { "" };
+
+    match link {
+        DeepLink::Start { session } => {
+            let Some(session) = settings::find_session(app, &session) else {
+                app.dialog()
+                    .message(format!("There is no saved session named \"{}\".", session))
+                    .title("Session Not Found")
+                    .kind(MessageDialogKind::Error)
+                    .show(|_| {});
+                return;
+            };
+            let start = move |app: &AppHandle| match commands::start_session(app.clone(), session.id, app.state()) {
+                Ok(()) => tray::show_main_window(app),
+                Err(e) => eprintln!("Failed to start session from link: {}", e),
+            };
+            if replacing {
+                confirm(app, format!("Start \"{}\"?{}", session.name, replace_note), start);
+            } else {
+                start(app);
+            }
+        }
+        DeepLink::New { name, work_minutes, break_minutes, rounds } => {
+            // Someone else wrote this session, so always ask before running it
+            let message = format!(
+                "Start \"{}\" from a link?\n\n{} × {} min focus with {} min breaks.{}",
+                name, rounds, work_minutes, break_minutes, replace_note,
+            );
+            let session = build_session(name, work_minutes, break_minutes, rounds);
+            confirm(app, message, move |app| {
+                let id = session.id.clone();
+                let timer = app.state::<TimerManager>();
+                timer.load_session(session);
+                if let Err(e) = timer.start(app.clone()) {
+                    eprintln!("Failed to start session from link: {}", e);
+                    return;
+                }
+                events::emit(app, GaweEvent::SessionStarted { session_id: id });
+                tray::show_main_window(app);
+            });
+        }
+    }
+}
+
+fn confirm(app: &AppHandle, message: String, on_confirm: impl FnOnce(&AppHandle) + Send + 'static) {
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Start Session")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom("Start".to_string(), "Cancel".to_string()))
+        .show(move |confirmed| {
+            if confirmed {
+                on_confirm(&handle);
+            }
+        });
+}
+
+/// Alternates focus and break tasks, without a break after the last round.
+/// The session isn't added to the saved list.
+fn build_session(name: String, work_minutes: u32, break_minutes: u32, rounds: u32) -> Session {
+    let id = format!("link-{:016x}", rand::random::<u64>());
+    let mut tasks = Vec::new();
+    for round in 0..rounds {
+        tasks.push(Task {
+            id: format!("{}-{}", id, tasks.len()),
+            name: "Focus Task".to_string(),
+            duration_minutes: work_minutes,
+            task_type: TaskType::Work,
+            audio: None,
+        });
+        if round + 1 < rounds {
+            tasks.push(Task {
+                id: format!("{}-{}", id, tasks.len()),
+                name: "Break".to_string(),
+                duration_minutes: break_minutes,
+                task_type: TaskType::Break,
+                audio: None,
+            });
+        }
+    }
+    Session {
+        id,
+        name,
+        tasks,
+        created_at: chrono::Utc::now(),
+        work_audio: None,
+        break_audio: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(link: &str) -> Result<DeepLink, String> {
+        parse(&Url::parse(link).unwrap())
+    }
+
+    #[test]
+    fn start_takes_session_id_or_name() {
+        assert_eq!(
+            parse_str("gawe://start?session=Deep%20Work"),
+            Ok(DeepLink::Start { session: "Deep Work".to_string() }),
+        );
+    }
+
+    #[test]
+    fn start_without_session_is_refused() {
+        assert!(parse_str("gawe://start").is_err());
+        assert!(parse_str("gawe://start?session=%20%20").is_err());
+    }
+
+    #[test]
+    fn new_fills_in_defaults() {
+        assert_eq!(
+            parse_str("gawe://new"),
+            Ok(DeepLink::New {
+                name: "Session from Link".to_string(),
+                work_minutes: 25,
+                break_minutes: 5,
+                rounds: 4,
+            }),
+        );
+    }
+
+    #[test]
+    fn new_reads_every_field() {
+        assert_eq!(
+            parse_str("gawe://new?work=50&break=10&rounds=3&name=Planning"),
+            Ok(DeepLink::New {
+                name: "Planning".to_string(),
+                work_minutes: 50,
+                break_minutes: 10,
+                rounds: 3,
+            }),
+        );
+    }
+
+    #[test]
+    fn out_of_range_numbers_are_refused() {
+        assert!(parse_str("gawe://new?work=0").is_err());
+        assert!(parse_str("gawe://new?work=181").is_err());
+        assert!(parse_str("gawe://new?break=1000").is_err());
+        assert!(parse_str("gawe://new?rounds=13").is_err());
+        assert!(parse_str("gawe://new?work=180&break=180&rounds=12").is_ok());
+    }
+
+    #[test]
+    fn non_numeric_values_are_refused() {
+        assert!(parse_str("gawe://new?work=abc").is_err());
+        assert!(parse_str("gawe://new?rounds=-1").is_err());
+        assert!(parse_str("gawe://new?break=2.5").is_err());
+        assert!(parse_str("gawe://new?work=").is_err());
+    }
+
+    #[test]
+    fn other_schemes_and_hosts_are_refused() {
+        assert!(parse_str("https://start?session=Deep%20Work").is_err());
+        assert!(parse_str("gawe://delete?session=Deep%20Work").is_err());
+        assert!(parse_str("gawe:start").is_err());
+    }
+
+    #[test]
+    fn long_names_are_truncated() {
+        let link = format!("gawe://new?name={}", "é".repeat(100));
+        let Ok(DeepLink::New { name, .. }) = parse_str(&link) else {
+            panic!("expected a new-session link");
+        };
+        assert_eq!(name, "é".repeat(MAX_NAME_LENGTH));
+    }
+
+    #[test]
+    fn blank_name_falls_back_to_default() {
+        let Ok(DeepLink::New { name, .. }) = parse_str("gawe://new?name=%20") else {
+            panic!("expected a new-session link");
+        };
+        assert_eq!(name, "Session from Link");
+    }
+}
@@ -0,0 +1,451 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::error::GaweError;
+use crate::events::{self, GaweEvent};
+use crate::models::{Task, TaskType};
+use crate::settings::{self, DistractionSettings};
+use crate::timer::TimerManager;
+
+const POLL_INTERVAL_SECONDS: u64 = 2;
+
+/// Reports the application owning the focused window.
+/// Only the application class (e.g. "firefox") is ever read; window titles
+/// are left alone so document names and chat contents never reach the app.
+pub trait ForegroundAppSource: Send + 'static {
+    fn focused_app(&mut self) -> Option<String>;
+}
+
+/// Reads `_NET_ACTIVE_WINDOW` from the root window and the `WM_CLASS` of that window.
+#[cfg(target_os = "linux")]
+pub struct X11AppSource {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+    active_window_atom: x11rb::protocol::xproto::Atom,
+}
+
+#[cfg(target_os = "linux")]
+impl X11AppSource {
+    pub fn connect() -> Result<Self, Box<dyn std::error::Error>> {
+        use x11rb::connection::Connection;
+        use x11rb::protocol::xproto::ConnectionExt;
+
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        let active_window_atom = conn.intern_atom(false, b"_NET_ACTIVE_WINDOW")?.reply()?.atom;
+
+        Ok(Self { conn, root, active_window_atom })
+    }
+
+    fn active_window(&self) -> Option<x11rb::protocol::xproto::Window> {
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        let reply = self.conn
+            .get_property(false, self.root, self.active_window_atom, AtomEnum::WINDOW, 0, 1)
+            .ok()?
+            .reply()
+            .ok()?;
+        let window = reply.value32()?.next()?;
+        (window != 0).then_some(window)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl ForegroundAppSource for X11AppSource {
+    fn focused_app(&mut self) -> Option<String> {
+        use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+        let window = self.active_window()?;
+        let reply = self.conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)
+            .ok()?
+            .reply()
+            .ok()?;
+
+        // WM_CLASS is "instance\0class\0"; the class is the stable application name
+        let mut parts = reply.value.split(|b| *b == 0).filter(|part| !part.is_empty());
+        let instance = parts.next();
+        let class = parts.next().or(instance)?;
+        Some(String::from_utf8_lossy(class).into_owned())
+    }
+}
+
+/// The native source for the current platform, if one is available.
+pub fn default_source() -> Option<Box<dyn ForegroundAppSource>> {
+    #[cfg(target_os = "linux")]
+    {
+        match X11AppSource::connect() {
+            Ok(source) => Some(Box::new(source)),
+            Err(e) => {
+                eprintln!("Active-window tracking unavailable: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppCategory {
+    Allowed,
+    Distracting,
+    Neutral,
+}
+
+/// User-defined app lists, matched case-insensitively against the app class.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppRules {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl AppRules {
+    pub fn classify(&self, app: &str) -> AppCategory {
+        let matches = |list: &[String]| list.iter().any(|entry| entry.eq_ignore_ascii_case(app));
+
+        if matches(&self.deny) {
+            AppCategory::Distracting
+        } else if matches(&self.allow) {
+            AppCategory::Allowed
+        } else if !self.allow.is_empty() {
+            // With an allow list in place, anything not on it counts as drift
+            AppCategory::Distracting
+        } else {
+            AppCategory::Neutral
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DistractionDetected {
+    pub app: String,
+    pub session_id: String,
+    pub task_id: String,
+    pub task_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppUsage {
+    pub app: String,
+    pub category: AppCategory,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFocusSummary {
+    pub session_id: String,
+    pub task_id: String,
+    pub task_name: String,
+    pub focused_seconds: u64,
+    pub distracted_seconds: u64,
+    pub distractions: u32,
+    /// Logged by hand, e.g. a colleague stopping by.
+    pub interruptions: u32,
+    pub apps: Vec<AppUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptionLogged {
+    pub session_id: String,
+    pub task_id: String,
+    pub task_name: String,
+    pub interruptions: u32,
+}
+
+pub struct DistractionState {
+    pub is_enabled: bool,
+    pub rules: AppRules,
+    pub summaries: Vec<TaskFocusSummary>,
+    pub last_category: Option<AppCategory>,
+}
+
+impl DistractionState {
+    /// Counts one poll spent in `focused` against `task`. Returns `true` when
+    /// this poll switched into a distracting app.
+    fn record(&mut self, session_id: &str, task: &Task, focused: &str) -> bool {
+        let category = self.rules.classify(focused);
+
+        // Only report the switch into a distracting app, not every poll spent there
+        let newly_distracted = category == AppCategory::Distracting
+            && self.last_category != Some(AppCategory::Distracting);
+        self.last_category = Some(category);
+
+        let summary = summary_for(&mut self.summaries, session_id, task);
+
+        if category == AppCategory::Distracting {
+            summary.distracted_seconds += POLL_INTERVAL_SECONDS;
+        } else {
+            summary.focused_seconds += POLL_INTERVAL_SECONDS;
+        }
+        if newly_distracted {
+            summary.distractions += 1;
+        }
+        match summary.apps.iter_mut().find(|usage| usage.app == focused) {
+            Some(usage) => usage.seconds += POLL_INTERVAL_SECONDS,
+            None => summary.apps.push(AppUsage {
+                app: focused.to_string(),
+                category,
+                seconds: POLL_INTERVAL_SECONDS,
+            }),
+        }
+        newly_distracted
+    }
+}
+
+pub struct DistractionManager {
+    pub state: Arc<Mutex<DistractionState>>,
+}
+
+impl Default for DistractionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DistractionManager {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(DistractionState {
+                is_enabled: false,
+                rules: AppRules::default(),
+                summaries: Vec::new(),
+                last_category: None,
+            })),
+        }
+    }
+
+    /// Polls `source` while a Work task is running. Summaries are kept in
+    /// memory only and cleared whenever a different session starts.
+    pub fn start_monitoring(&self, app: AppHandle, mut source: Box<dyn ForegroundAppSource>) {
+        let state = self.state.clone();
+
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_secs(POLL_INTERVAL_SECONDS));
+
+            if !state.lock().unwrap().is_enabled {
+                continue;
+            }
+
+            let Some((session_id, task)) = app.state::<TimerManager>().running_task() else {
+                state.lock().unwrap().last_category = None;
+                continue;
+            };
+            if !matches!(task.task_type, TaskType::Work) {
+                continue;
+            }
+
+            let Some(focused) = source.focused_app() else {
+                continue;
+            };
+
+            let newly_distracted = state.lock().unwrap().record(&session_id, &task, &focused);
+            if newly_distracted {
+                events::emit(&app, GaweEvent::DistractionDetected(DistractionDetected {
+                    app: focused,
+                    session_id,
+                    task_id: task.id,
+                    task_name: task.name,
+                }));
+            }
+        });
+    }
+
+    pub fn settings(&self) -> DistractionSettings {
+        let guard = self.state.lock().unwrap();
+        DistractionSettings {
+            enabled: guard.is_enabled,
+            rules: guard.rules.clone(),
+        }
+    }
+
+    pub fn apply_settings(&self, settings: &DistractionSettings) {
+        let mut guard = self.state.lock().unwrap();
+        guard.is_enabled = settings.enabled;
+        guard.rules = settings.rules.clone();
+        // Past usage keeps the category it was recorded under; only new polls use the new lists
+        guard.last_category = None;
+    }
+
+    /// Applies, persists and broadcasts new settings.
+    pub fn update_settings(&self, app: &AppHandle, settings: DistractionSettings) -> Result<(), GaweError> {
+        self.apply_settings(&settings);
+        settings::save(app, settings::DISTRACTION_KEY, &settings)?;
+        events::emit(app, GaweEvent::DistractionSettingsChanged(settings));
+        Ok(())
+    }
+
+    pub fn get_summaries(&self) -> Vec<TaskFocusSummary> {
+        self.state.lock().unwrap().summaries.clone()
+    }
+
+    /// Counts an interruption against the running task; works whether or not
+    /// app tracking is on. Returns `false` when nothing is being timed.
+    pub fn log_interruption(&self, app: &AppHandle) -> bool {
+        let Some((session_id, task)) = app.state::<TimerManager>().running_task() else {
+            return false;
+        };
+        let mut guard = self.state.lock().unwrap();
+        let summary = summary_for(&mut guard.summaries, &session_id, &task);
+        summary.interruptions += 1;
+        let logged = InterruptionLogged {
+            session_id,
+            task_id: task.id,
+            task_name: task.name,
+            interruptions: summary.interruptions,
+        };
+        drop(guard);
+
+        events::emit(app, GaweEvent::InterruptionLogged(logged));
+        true
+    }
+}
+
+/// The summary for `task`, starting over when a different session is running.
+fn summary_for<'a>(summaries: &'a mut Vec<TaskFocusSummary>, session_id: &str, task: &Task) -> &'a mut TaskFocusSummary {
+    if summaries.iter().any(|s| s.session_id != session_id) {
+        summaries.clear();
+    }
+    match summaries.iter().position(|s| s.task_id == task.id) {
+        Some(index) => &mut summaries[index],
+        None => {
+            summaries.push(TaskFocusSummary {
+                session_id: session_id.to_string(),
+                task_id: task.id.clone(),
+                task_name: task.name.clone(),
+                focused_seconds: 0,
+                distracted_seconds: 0,
+                distractions: 0,
+                interruptions: 0,
+                apps: Vec::new(),
+            });
+            summaries.last_mut().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use super::*;
+
+    /// Replays a fixed sequence of focused apps, one per poll.
+    struct ScriptedAppSource {
+        apps: VecDeque<Option<String>>,
+    }
+
+    impl ForegroundAppSource for ScriptedAppSource {
+        fn focused_app(&mut self) -> Option<String> {
+            self.apps.pop_front().flatten()
+        }
+    }
+
+    fn rules(allow: &[&str], deny: &[&str]) -> AppRules {
+        AppRules {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn work_task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            name: "Write report".to_string(),
+            duration_minutes: 25,
+            task_type: TaskType::Work,
+            audio: None,
+        }
+    }
+
+    fn state_with(rules: AppRules) -> DistractionState {
+        DistractionState {
+            is_enabled: true,
+            rules,
+            summaries: Vec::new(),
+            last_category: None,
+        }
+    }
+
+    /// Polls `source` until it runs dry, the way the monitoring loop does.
+    fn replay(state: &mut DistractionState, session_id: &str, task: &Task, mut source: ScriptedAppSource) -> u32 {
+        let mut detected = 0;
+        while !source.apps.is_empty() {
+            if let Some(focused) = source.focused_app() {
+                if state.record(session_id, task, &focused) {
+                    detected += 1;
+                }
+            }
+        }
+        detected
+    }
+
+    fn apps(names: &[Option<&str>]) -> ScriptedAppSource {
+        ScriptedAppSource { apps: names.iter().map(|name| name.map(str::to_string)).collect() }
+    }
+
+    #[test]
+    fn classify_without_lists_is_neutral() {
+        assert_eq!(AppRules::default().classify("firefox"), AppCategory::Neutral);
+    }
+
+    #[test]
+    fn classify_ignores_case() {
+        let rules = rules(&["Code"], &["Slack"]);
+        assert_eq!(rules.classify("code"), AppCategory::Allowed);
+        assert_eq!(rules.classify("SLACK"), AppCategory::Distracting);
+    }
+
+    #[test]
+    fn classify_deny_wins_over_allow() {
+        assert_eq!(rules(&["firefox"], &["firefox"]).classify("firefox"), AppCategory::Distracting);
+    }
+
+    #[test]
+    fn classify_outside_allow_list_is_distracting() {
+        assert_eq!(rules(&["code"], &[]).classify("discord"), AppCategory::Distracting);
+        assert_eq!(rules(&[], &["discord"]).classify("code"), AppCategory::Neutral);
+    }
+
+    #[test]
+    fn accumulates_focused_and_distracted_time() {
+        let mut state = state_with(rules(&[], &["slack"]));
+        let task = work_task("t1");
+        let source = apps(&[Some("code"), Some("slack"), Some("slack"), None, Some("code")]);
+
+        assert_eq!(replay(&mut state, "s1", &task, source), 1);
+
+        let summary = &state.summaries[0];
+        assert_eq!(summary.focused_seconds, 2 * POLL_INTERVAL_SECONDS);
+        assert_eq!(summary.distracted_seconds, 2 * POLL_INTERVAL_SECONDS);
+        assert_eq!(summary.distractions, 1);
+        let slack = summary.apps.iter().find(|usage| usage.app == "slack").unwrap();
+        assert_eq!(slack.category, AppCategory::Distracting);
+        assert_eq!(slack.seconds, 2 * POLL_INTERVAL_SECONDS);
+    }
+
+    #[test]
+    fn counts_each_switch_into_a_distraction() {
+        let mut state = state_with(rules(&[], &["slack", "youtube"]));
+        let task = work_task("t1");
+        let source = apps(&[Some("slack"), Some("youtube"), Some("code"), Some("slack")]);
+
+        // Going straight from one distraction to another is still one drift
+        assert_eq!(replay(&mut state, "s1", &task, source), 2);
+        assert_eq!(state.summaries[0].distractions, 2);
+    }
+
+    #[test]
+    fn starts_over_for_a_new_session() {
+        let mut state = state_with(AppRules::default());
+        replay(&mut state, "s1", &work_task("t1"), apps(&[Some("code")]));
+        replay(&mut state, "s2", &work_task("t1"), apps(&[Some("code"), Some("code")]));
+
+        assert_eq!(state.summaries.len(), 1);
+        assert_eq!(state.summaries[0].session_id, "s2");
+        assert_eq!(state.summaries[0].focused_seconds, 2 * POLL_INTERVAL_SECONDS);
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::audio::AudioError;
+
+/// Error returned by every command, tagged by `kind` like `AudioError` so the
+/// frontend and CLI can match on it instead of parsing messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum GaweError {
+    NoSessionLoaded,
+    AlreadyRunning,
+    /// Something needs a running task, e.g. logging an interruption.
+    NotRunning,
+    SessionNotFound { session: String },
+    InvalidSession { message: String },
+    Audio { error: AudioError },
+    /// Reading or writing the settings store failed.
+    Io { message: String },
+    Window { message: String },
+    /// A control-socket request that couldn't be parsed.
+    InvalidRequest { message: String },
+}
+
+impl GaweError {
+    pub fn io(error: impl fmt::Display) -> Self {
+        GaweError::Io { message: error.to_string() }
+    }
+}
+
+impl fmt::Display for GaweError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GaweError::NoSessionLoaded => write!(f, "No session is loaded"),
+            GaweError::AlreadyRunning => write!(f, "The timer is already running"),
+            GaweError::NotRunning => write!(f, "No task is running"),
+            GaweError::SessionNotFound { session } => write!(f, "No saved session named \"{}\"", session),
+            GaweError::InvalidSession { message } => write!(f, "Invalid session: {}", message),
+            GaweError::Audio { error } => write!(f, "{}", error),
+            GaweError::Io { message } => write!(f, "Failed to save settings: {}", message),
+            GaweError::Window { message } => write!(f, "Window error: {}", message),
+            GaweError::InvalidRequest { message } => write!(f, "Invalid request: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for GaweError {}
+
+impl From<AudioError> for GaweError {
+    fn from(error: AudioError) -> Self {
+        GaweError::Audio { error }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::audio::{AudioError, NowPlaying};
+use crate::distraction::{DistractionDetected, InterruptionLogged};
+use crate::power::PowerEvent;
+use crate::settings::{
+    ActivitySettings, AnnouncementSettings, DistractionSettings, FadeSettings, HotkeySettings, SoundSettings,
+    TickSettings,
+};
+use crate::timer::TimerUpdate;
+
+/// Bumped whenever an existing event's payload changes shape.
+pub const EVENT_VERSION: u32 = 1;
+/// Every event also goes out under this name, wrapped in an `EventEnvelope`.
+pub const EVENT_NAME: &str = "gawe-event";
+const MAX_HISTORY: usize = 200;
+
+/// Why the timer moved on from a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdvanceReason {
+    /// The task's time ran out.
+    Completed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum GaweEvent {
+    TimerUpdate(TimerUpdate),
+    SessionStarted {
+        session_id: String,
+    },
+    TaskChanged {
+        session_id: String,
+        task_id: String,
+        task_name: String,
+        task_index: usize,
+        is_break: bool,
+        reason: AdvanceReason,
+    },
+    SessionFinished {
+        session_id: String,
+        reason: AdvanceReason,
+    },
+    /// The timer was paused by a lock or suspend and the user is back.
+    ResumePrompt(TimerUpdate),
+    ActivityWarning {
+        idle_seconds: u64,
+    },
+    DistractionDetected(DistractionDetected),
+    InterruptionLogged(InterruptionLogged),
+    Power {
+        event: PowerEvent,
+    },
+    NowPlaying(NowPlaying),
+    AudioError {
+        error: AudioError,
+    },
+    AudioOutputChanged {
+        device: String,
+    },
+    LibraryUpdated {
+        tracks: usize,
+    },
+    ActivitySettingsChanged(ActivitySettings),
+    DistractionSettingsChanged(DistractionSettings),
+    SoundSettingsChanged(SoundSettings),
+    AnnouncementSettingsChanged(AnnouncementSettings),
+    FadeSettingsChanged(FadeSettings),
+    TickSettingsChanged(TickSettings),
+    HotkeySettingsChanged(HotkeySettings),
+}
+
+impl GaweEvent {
+    /// The name and payload this event had before the bus existed, which the
+    /// frontend still listens for.
+    fn legacy(&self) -> (&'static str, Value) {
+        match self {
+            GaweEvent::TimerUpdate(update) => ("timer-update", json!(update)),
+            GaweEvent::SessionStarted { session_id } => ("session-started", json!(session_id)),
+            GaweEvent::TaskChanged { task_name, .. } => ("task-changed", json!(task_name)),
+            GaweEvent::SessionFinished { .. } => ("session-finished", Value::Null),
+            GaweEvent::ResumePrompt(update) => ("resume-prompt", json!(update)),
+            GaweEvent::ActivityWarning { .. } => ("activity-warning", Value::Null),
+            GaweEvent::DistractionDetected(detected) => ("distraction-detected", json!(detected)),
+            GaweEvent::InterruptionLogged(logged) => ("interruption-logged", json!(logged)),
+            GaweEvent::Power { event } => ("power-event", json!(event)),
+            GaweEvent::NowPlaying(now_playing) => ("now-playing", json!(now_playing)),
+            GaweEvent::AudioError { error } => ("audio-error", json!(error)),
+            GaweEvent::AudioOutputChanged { device } => ("audio-output-changed", json!(device)),
+            GaweEvent::LibraryUpdated { tracks } => ("library-updated", json!(tracks)),
+            GaweEvent::ActivitySettingsChanged(settings) => ("activity-settings-changed", json!(settings)),
+            GaweEvent::DistractionSettingsChanged(settings) => ("distraction-settings-changed", json!(settings)),
+            GaweEvent::SoundSettingsChanged(settings) => ("sound-settings-changed", json!(settings)),
+            GaweEvent::AnnouncementSettingsChanged(settings) => ("announcement-settings-changed", json!(settings)),
+            GaweEvent::FadeSettingsChanged(settings) => ("fade-settings-changed", json!(settings)),
+            GaweEvent::TickSettingsChanged(settings) => ("tick-settings-changed", json!(settings)),
+            GaweEvent::HotkeySettingsChanged(settings) => ("hotkey-settings-changed", json!(settings)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope {
+    pub version: u32,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: GaweEvent,
+}
+
+/// Returns `false` to unsubscribe, e.g. once an IPC client has gone away.
+pub type Subscriber = Arc<dyn Fn(&EventEnvelope) -> bool + Send + Sync>;
+
+pub struct EventState {
+    pub subscribers: Vec<Subscriber>,
+    /// Recent events, oldest first; ticks are left out so they don't crowd the rest out.
+    pub history: VecDeque<EventEnvelope>,
+}
+
+pub struct EventBus {
+    pub state: Arc<Mutex<EventState>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(EventState {
+                subscribers: Vec::new(),
+                history: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Subscribers run on the emitting thread, sometimes with the timer locked,
+    /// so they should hand work off (e.g. over a channel) rather than call back in.
+    pub fn subscribe(&self, subscriber: impl Fn(&EventEnvelope) -> bool + Send + Sync + 'static) {
+        self.state.lock().unwrap().subscribers.push(Arc::new(subscriber));
+    }
+
+    pub fn history(&self) -> Vec<EventEnvelope> {
+        self.state.lock().unwrap().history.iter().cloned().collect()
+    }
+
+    fn dispatch(&self, app: &AppHandle, event: GaweEvent) {
+        let (name, payload) = event.legacy();
+        let _ = app.emit(name, payload);
+
+        let envelope = EventEnvelope {
+            version: EVENT_VERSION,
+            timestamp: Utc::now(),
+            event,
+        };
+        let _ = app.emit(EVENT_NAME, &envelope);
+
+        let subscribers = {
+            let mut guard = self.state.lock().unwrap();
+            if !matches!(envelope.event, GaweEvent::TimerUpdate(_)) {
+                if guard.history.len() == MAX_HISTORY {
+                    guard.history.pop_front();
+                }
+                guard.history.push_back(envelope.clone());
+            }
+            guard.subscribers.clone()
+        };
+        // Called without the lock so a subscriber may emit or subscribe in turn
+        let gone: Vec<Subscriber> = subscribers.into_iter().filter(|s| !s(&envelope)).collect();
+        if !gone.is_empty() {
+            self.state.lock().unwrap().subscribers.retain(|s| !gone.iter().any(|g| Arc::ptr_eq(s, g)));
+        }
+    }
+}
+
+/// Sends `event` to the frontend, under both its own name and `gawe-event`,
+/// and to every internal subscriber.
+pub fn emit(app: &AppHandle, event: GaweEvent) {
+    app.state::<EventBus>().dispatch(app, event);
+}
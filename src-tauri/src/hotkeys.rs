@@ -0,0 +1,140 @@
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use crate::audio::AudioManager;
+use crate::distraction::DistractionManager;
+use crate::error::GaweError;
+use crate::events::{self, GaweEvent};
+use crate::mini;
+use crate::settings::{self, HotkeySettings};
+use crate::timer::TimerManager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HotkeyAction {
+    StartPause,
+    Skip,
+    LogInterruption,
+    PauseMusic,
+    ToggleMiniTimer,
+}
+
+impl HotkeyAction {
+    fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::StartPause => "Start/Pause",
+            HotkeyAction::Skip => "Skip Task",
+            HotkeyAction::LogInterruption => "Log Interruption",
+            HotkeyAction::PauseMusic => "Play/Pause Music",
+            HotkeyAction::ToggleMiniTimer => "Mini Timer",
+        }
+    }
+}
+
+/// A binding that couldn't be registered, and why.
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyConflict {
+    pub action: HotkeyAction,
+    pub accelerator: String,
+    pub reason: String,
+}
+
+pub struct HotkeyManager {
+    pub conflicts: Arc<Mutex<Vec<HotkeyConflict>>>,
+}
+
+impl Default for HotkeyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotkeyManager {
+    pub fn new() -> Self {
+        Self {
+            conflicts: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Replaces every registered shortcut with `hotkeys`. Bindings that fail to
+    /// parse, repeat one another or are held by another application are skipped
+    /// and returned as conflicts.
+    pub fn apply(&self, app: &AppHandle, hotkeys: &HotkeySettings) -> Vec<HotkeyConflict> {
+        let shortcuts = app.global_shortcut();
+        if let Err(e) = shortcuts.unregister_all() {
+            eprintln!("Failed to clear global shortcuts: {}", e);
+        }
+
+        let mut conflicts = Vec::new();
+        let mut registered: Vec<(Shortcut, HotkeyAction)> = Vec::new();
+        for (&action, accelerator) in &hotkeys.bindings {
+            if accelerator.trim().is_empty() {
+                continue;
+            }
+            let conflict = |reason: String| HotkeyConflict {
+                action,
+                accelerator: accelerator.clone(),
+                reason,
+            };
+
+            let shortcut = match accelerator.parse::<Shortcut>() {
+                Ok(shortcut) => shortcut,
+                Err(e) => {
+                    conflicts.push(conflict(format!("Not a valid shortcut: {}", e)));
+                    continue;
+                }
+            };
+            if let Some((_, other)) = registered.iter().find(|(s, _)| *s == shortcut) {
+                conflicts.push(conflict(format!("Already used for {}", other.label())));
+                continue;
+            }
+
+            let result = shortcuts.on_shortcut(shortcut, move |app, _, event| {
+                if event.state == ShortcutState::Pressed {
+                    perform(app, action);
+                }
+            });
+            match result {
+                Ok(()) => registered.push((shortcut, action)),
+                Err(e) => conflicts.push(conflict(format!("In use by another application: {}", e))),
+            }
+        }
+
+        *self.conflicts.lock().unwrap() = conflicts.clone();
+        conflicts
+    }
+
+    /// Applies, persists and broadcasts new bindings; conflicts are still saved
+    /// so the user can fix them once the other application lets go.
+    pub fn update(&self, app: &AppHandle, hotkeys: HotkeySettings) -> Result<Vec<HotkeyConflict>, GaweError> {
+        let conflicts = self.apply(app, &hotkeys);
+        settings::save(app, settings::HOTKEYS_KEY, &hotkeys)?;
+        events::emit(app, GaweEvent::HotkeySettingsChanged(hotkeys));
+        Ok(conflicts)
+    }
+
+    pub fn get_conflicts(&self) -> Vec<HotkeyConflict> {
+        self.conflicts.lock().unwrap().clone()
+    }
+}
+
+fn perform(app: &AppHandle, action: HotkeyAction) {
+    let timer = app.state::<TimerManager>();
+    let result = match action {
+        HotkeyAction::StartPause => timer.toggle(app.clone()),
+        HotkeyAction::Skip => timer.skip(app),
+        HotkeyAction::LogInterruption => match app.state::<DistractionManager>().log_interruption(app) {
+            true => Ok(()),
+            false => Err(GaweError::NotRunning),
+        },
+        HotkeyAction::PauseMusic => {
+            app.state::<AudioManager>().pause();
+            Ok(())
+        }
+        HotkeyAction::ToggleMiniTimer => mini::toggle(app).map_err(|e| GaweError::Window { message: e.to_string() }),
+    };
+    if let Err(e) = result {
+        eprintln!("{} shortcut: {}", action.label(), e);
+    }
+}
@@ -0,0 +1,97 @@
+use tauri::{AppHandle, Manager};
+use crate::commands;
+use crate::events::{self, GaweEvent};
+use crate::timer::TimerManager;
+use crate::tray;
+
+/// What a launch asks of the app, e.g. from a desktop shortcut.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchAction {
+    Start(String),
+    Pause,
+}
+
+/// Picks `--start <session-id>` and `--pause` out of the launch arguments,
+/// leaving anything else (e.g. what the OS adds) alone.
+pub fn parse_args(args: &[String]) -> Vec<LaunchAction> {
+    let mut actions = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => match args.next() {
+                Some(id) => actions.push(LaunchAction::Start(id.clone())),
+                None => eprintln!("--start needs a session id"),
+            },
+            "--pause" => actions.push(LaunchAction::Pause),
+            _ => {}
+        }
+    }
+    actions
+}
+
+/// Applies launch arguments, both our own and those forwarded by a second launch.
+pub fn handle_args(app: &AppHandle, args: &[String]) {
+    for action in parse_args(args) {
+        match action {
+            LaunchAction::Start(id) => {
+                if let Err(e) = commands::start_session(app.clone(), id, app.state()) {
+                    eprintln!("{}", e);
+                }
+            }
+            LaunchAction::Pause => app.state::<TimerManager>().pause(),
+        }
+    }
+    if let Some(status) = app.state::<TimerManager>().get_status() {
+        events::emit(app, GaweEvent::TimerUpdate(status));
+    }
+}
+
+/// Called in the first instance when GaWe is launched again; the second
+/// process exits once its arguments are handed over.
+pub fn on_second_launch(app: &AppHandle, argv: Vec<String>) {
+    let args = argv.get(1..).unwrap_or_default();
+    // A bare relaunch means "open the app", which would otherwise look like nothing happened
+    if parse_args(args).is_empty() {
+        tray::show_main_window(app);
+    }
+    handle_args(app, args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Vec<LaunchAction> {
+        parse_args(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn start_and_pause() {
+        assert_eq!(parse(&["--start", "deep-work"]), vec![LaunchAction::Start("deep-work".to_string())]);
+        assert_eq!(parse(&["--pause"]), vec![LaunchAction::Pause]);
+    }
+
+    #[test]
+    fn keeps_order_of_several_actions() {
+        assert_eq!(
+            parse(&["--pause", "--start", "deep-work"]),
+            vec![LaunchAction::Pause, LaunchAction::Start("deep-work".to_string())],
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_arguments() {
+        assert_eq!(parse(&[]), vec![]);
+        assert_eq!(parse(&["-psn_0_12345", "--pause", "gawe://new"]), vec![LaunchAction::Pause]);
+    }
+
+    #[test]
+    fn start_without_id_is_dropped() {
+        assert_eq!(parse(&["--start"]), vec![]);
+    }
+
+    #[test]
+    fn start_takes_the_next_argument_as_is() {
+        assert_eq!(parse(&["--start", "--pause"]), vec![LaunchAction::Start("--pause".to_string())]);
+    }
+}
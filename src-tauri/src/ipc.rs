@@ -0,0 +1,273 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::error::GaweError;
+use crate::timer::TimerUpdate;
+
+const SOCKET_NAME: &str = "gawe.sock";
+
+/// One request per line of JSON on the control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum IpcRequest {
+    /// Starts the named (or id'd) session, or resumes the loaded one.
+    Start { session: Option<String> },
+    Pause,
+    Skip,
+    Status,
+    /// Keeps the connection open and streams every event as a JSON line.
+    Subscribe,
+}
+
+/// Every reply carries the timer status after the request was handled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponse {
+    pub error: Option<GaweError>,
+    pub status: Option<TimerUpdate>,
+}
+
+/// Lives in the per-user runtime dir when there is one, so other users can't
+/// reach it, and otherwise in a private per-user folder under the temp dir.
+/// `None` when there is no runtime dir and the user name is unknown.
+pub fn socket_path() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join(SOCKET_NAME));
+    }
+    let user = ["USER", "LOGNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|user| !user.is_empty()))?;
+    Some(std::env::temp_dir().join(format!("gawe-{}", user)).join(SOCKET_NAME))
+}
+
+#[cfg(unix)]
+pub use unix::{remove_socket, send, start_server, subscribe};
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::{self, DirBuilder, Permissions};
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::path::Path;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use tauri::{AppHandle, Manager};
+    use super::{socket_path, IpcRequest, IpcResponse};
+    use crate::commands;
+    use crate::error::GaweError;
+    use crate::events::{self, EventBus, GaweEvent};
+    use crate::settings;
+    use crate::timer::TimerManager;
+
+    const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Listens on the control socket in a background thread, one thread per client.
+    pub fn start_server(app: AppHandle) {
+        let Some(path) = socket_path() else {
+            eprintln!("Control socket disabled: neither XDG_RUNTIME_DIR nor USER is set");
+            return;
+        };
+        if UnixStream::connect(&path).is_ok() {
+            eprintln!("Another GaWe instance already owns {}", path.display());
+            return;
+        }
+        if let Err(e) = prepare_dir(&path) {
+            eprintln!("Control socket disabled: {}", e);
+            return;
+        }
+        // Left behind by a previous run that didn't exit cleanly
+        let _ = fs::remove_file(&path);
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to open control socket {}: {}", path.display(), e);
+                return;
+            }
+        };
+        if let Err(e) = fs::set_permissions(&path, Permissions::from_mode(0o600)) {
+            eprintln!("Failed to restrict control socket: {}", e);
+        }
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let app = app.clone();
+                        thread::spawn(move || serve(&app, stream));
+                    }
+                    Err(e) => eprintln!("Control socket error: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Makes sure only we can reach the socket's folder before binding, since
+    /// the socket itself is connectable until its mode is set. The runtime dir
+    /// is private already; the temp-dir fallback is created 0700 and must
+    /// still be ours and private if it already exists.
+    fn prepare_dir(path: &Path) -> Result<(), String> {
+        let Some(dir) = path.parent() else {
+            return Err(format!("{} has no parent folder", path.display()));
+        };
+        if std::env::var_os("XDG_RUNTIME_DIR").is_some_and(|d| Path::new(&d) == dir) {
+            return Ok(());
+        }
+        match DirBuilder::new().mode(0o700).create(dir) {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("Failed to create {}: {}", dir.display(), e)),
+        }
+
+        // symlink_metadata so a planted link to someone else's folder is refused
+        let metadata = fs::symlink_metadata(dir).map_err(|e| e.to_string())?;
+        // SAFETY: getuid has no preconditions and cannot fail
+        let uid = unsafe { libc::getuid() };
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(format!("{} is not a private folder owned by this user", dir.display()));
+        }
+        Ok(())
+    }
+
+    pub fn remove_socket() {
+        if let Some(path) = socket_path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn connect() -> Result<UnixStream, String> {
+        let path = socket_path().ok_or("Can't find GaWe: neither XDG_RUNTIME_DIR nor USER is set")?;
+        UnixStream::connect(path).map_err(|_| "GaWe is not running".to_string())
+    }
+
+    fn serve(app: &AppHandle, stream: UnixStream) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            let response = match serde_json::from_str::<IpcRequest>(&line) {
+                Ok(IpcRequest::Subscribe) => return stream_events(app, writer),
+                Ok(request) => handle(app, request),
+                Err(e) => IpcResponse {
+                    error: Some(GaweError::InvalidRequest { message: e.to_string() }),
+                    status: app.state::<TimerManager>().get_status(),
+                },
+            };
+            let Ok(json) = serde_json::to_string(&response) else {
+                return;
+            };
+            if writeln!(writer, "{}", json).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Writes events until the client hangs up. The bus subscriber only queues
+    /// lines, since it may run with the timer locked.
+    fn stream_events(app: &AppHandle, mut writer: UnixStream) {
+        let (tx, rx) = mpsc::channel();
+        app.state::<EventBus>().subscribe(move |envelope| match serde_json::to_string(envelope) {
+            Ok(json) => tx.send(json).is_ok(),
+            Err(_) => true,
+        });
+        for json in rx {
+            if writeln!(writer, "{}", json).is_err() {
+                // Dropping `rx` unsubscribes on the next event
+                return;
+            }
+        }
+    }
+
+    /// Runs the request through the same code paths as the Tauri commands.
+    fn handle(app: &AppHandle, request: IpcRequest) -> IpcResponse {
+        // Pausing and skipping emit their own update; starting only reports on the next tick
+        let catch_up = matches!(request, IpcRequest::Start { .. });
+        let result = match request {
+            IpcRequest::Start { session: Some(name) } => match settings::find_session(app, &name) {
+                Some(session) => commands::start_session(app.clone(), session.id, app.state()),
+                None => Err(GaweError::SessionNotFound { session: name }),
+            },
+            IpcRequest::Start { session: None } => commands::start_timer(app.clone(), app.state()),
+            IpcRequest::Pause => commands::pause_timer(app.clone(), app.state()),
+            IpcRequest::Skip => commands::skip_task(app.clone(), app.state()),
+            IpcRequest::Status | IpcRequest::Subscribe => Ok(()),
+        };
+        let error = result.err();
+
+        let status = app.state::<TimerManager>().get_status();
+        if let Some(update) = status.as_ref().filter(|_| catch_up) {
+            events::emit(app, GaweEvent::TimerUpdate(update.clone()));
+        }
+        IpcResponse { error, status }
+    }
+
+    /// Sends one request to the running app and waits for its reply.
+    pub fn send(request: &IpcRequest) -> Result<IpcResponse, String> {
+        let mut stream = connect()?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT)).map_err(|e| e.to_string())?;
+
+        let json = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        writeln!(stream, "{}", json).map_err(|e| e.to_string())?;
+
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).map_err(|e| e.to_string())?;
+        serde_json::from_str(&line).map_err(|e| format!("Unexpected reply: {}", e))
+    }
+
+    /// Subscribes to the running app's events, passing each JSON line to
+    /// `on_line` until the app exits.
+    pub fn subscribe(mut on_line: impl FnMut(&str)) -> Result<(), String> {
+        let mut stream = connect()?;
+        let json = serde_json::to_string(&IpcRequest::Subscribe).map_err(|e| e.to_string())?;
+        writeln!(stream, "{}", json).map_err(|e| e.to_string())?;
+
+        for line in BufReader::new(stream).lines() {
+            on_line(&line.map_err(|e| e.to_string())?);
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn scratch_dir(name: &str) -> std::path::PathBuf {
+            let dir = std::env::temp_dir().join(format!("gawe-test-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            dir
+        }
+
+        #[test]
+        fn creates_a_private_folder() {
+            let dir = scratch_dir("fresh");
+            prepare_dir(&dir.join("gawe.sock")).unwrap();
+            assert_eq!(fs::metadata(&dir).unwrap().mode() & 0o777, 0o700);
+            // And accepts it on the next start
+            prepare_dir(&dir.join("gawe.sock")).unwrap();
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn refuses_a_folder_others_can_enter() {
+            let dir = scratch_dir("shared");
+            DirBuilder::new().mode(0o755).create(&dir).unwrap();
+            fs::set_permissions(&dir, Permissions::from_mode(0o755)).unwrap();
+            assert!(prepare_dir(&dir.join("gawe.sock")).is_err());
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn refuses_a_symlinked_folder() {
+            let target = scratch_dir("target");
+            let link = scratch_dir("link");
+            DirBuilder::new().mode(0o700).create(&target).unwrap();
+            std::os::unix::fs::symlink(&target, &link).unwrap();
+            assert!(prepare_dir(&link.join("gawe.sock")).is_err());
+            fs::remove_file(&link).unwrap();
+            fs::remove_dir_all(&target).unwrap();
+        }
+    }
+}
@@ -1,11 +1,36 @@
 pub mod activity;
+pub mod ambient;
+pub mod announce;
 pub mod audio;
+pub mod cli;
 pub mod commands;
+pub mod deeplink;
+pub mod distraction;
+pub mod error;
+pub mod events;
+pub mod hotkeys;
+pub mod instance;
+pub mod ipc;
+pub mod library;
+pub mod lifecycle;
+pub mod mini;
 pub mod models;
+pub mod output;
+pub mod playlist;
+pub mod power;
+pub mod settings;
+pub mod statusbar;
+pub mod sounds;
+pub mod tick;
 pub mod timer;
+pub mod tray;
 
 use activity::ActivityManager;
 use audio::AudioManager;
+use distraction::DistractionManager;
+use events::EventBus;
+use hotkeys::HotkeyManager;
+use library::LibraryManager;
 use tauri::Manager;
 use timer::TimerManager;
 
@@ -14,54 +39,132 @@ pub fn run() {
     let timer_manager = TimerManager::new();
     let activity_manager = ActivityManager::new();
     let audio_manager = AudioManager::new();
+    let distraction_manager = DistractionManager::new();
+    let library_manager = LibraryManager::new();
+    let hotkey_manager = HotkeyManager::new();
+    let event_bus = EventBus::new();
 
     tauri::Builder::default()
+        // Registered first so a second launch hands over its arguments before setting anything up
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            instance::on_second_launch(app, argv);
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(event_bus)
         .manage(timer_manager)
         .manage(activity_manager)
         .manage(audio_manager)
+        .manage(distraction_manager)
+        .manage(library_manager)
+        .manage(hotkey_manager)
         .setup(|app| {
+            app.state::<TimerManager>().restore_progress(app.handle());
             let activity_manager = app.state::<ActivityManager>();
+            activity_manager.apply_settings(&settings::load_activity(app.handle()));
             activity_manager.start_monitoring(app.handle().clone());
+            let audio_manager = app.state::<AudioManager>();
+            audio_manager.attach_app(app.handle().clone());
+            audio_manager.set_fades(settings::load(app.handle(), settings::FADES_KEY).unwrap_or_default());
+            audio_manager.set_sounds(settings::load(app.handle(), settings::SOUNDS_KEY).unwrap_or_default());
+            audio_manager.set_announcements(settings::load(app.handle(), settings::ANNOUNCEMENTS_KEY).unwrap_or_default());
+            audio_manager.set_tick(settings::load(app.handle(), settings::TICK_KEY).unwrap_or_default());
+            audio_manager.restore_output_device(settings::load(app.handle(), settings::OUTPUT_DEVICE_KEY).flatten());
+            app.state::<LibraryManager>().load(app.handle());
+            power::start_monitoring(app.handle().clone(), power::default_source());
+            let distraction_manager = app.state::<DistractionManager>();
+            distraction_manager.apply_settings(&settings::load(app.handle(), settings::DISTRACTION_KEY).unwrap_or_default());
+            if let Some(source) = distraction::default_source() {
+                distraction_manager.start_monitoring(app.handle().clone(), source);
+            }
+            let hotkeys = settings::load_hotkeys(app.handle());
+            for conflict in app.state::<HotkeyManager>().apply(app.handle(), &hotkeys) {
+                eprintln!("Global shortcut {} not registered: {}", conflict.accelerator, conflict.reason);
+            }
+            #[cfg(unix)]
+            ipc::start_server(app.handle().clone());
+            if let Err(e) = tray::create(app.handle()) {
+                eprintln!("Failed to create tray icon: {}", e);
+            }
+            let args: Vec<String> = std::env::args().skip(1).collect();
+            instance::handle_args(app.handle(), &args);
+            deeplink::start_listening(app.handle());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::start_timer,
             commands::pause_timer,
             commands::load_session,
+            commands::skip_task,
+            commands::start_session,
+            commands::get_close_behavior,
+            commands::set_close_behavior,
             commands::get_timer_status,
             commands::set_activity_monitoring,
+            commands::get_activity_settings,
+            commands::set_distraction_tracking,
+            commands::set_app_rules,
+            commands::get_distraction_settings,
+            commands::get_focus_summaries,
+            commands::get_event_history,
+            commands::log_interruption,
+            commands::toggle_mini_timer,
+            commands::get_hotkey_settings,
+            commands::set_hotkey_settings,
+            commands::get_hotkey_conflicts,
+            commands::list_tracks,
+            commands::search_tracks,
+            commands::get_library_folders,
+            commands::set_library_folders,
+            commands::rescan_library,
             commands::play_music,
+            commands::play_playlist,
+            commands::audio_status,
+            commands::seek_music,
+            commands::list_output_devices,
+            commands::set_output_device,
+            commands::next_track,
+            commands::previous_track,
+            commands::set_repeat_mode,
+            commands::set_shuffle,
             commands::pause_music,
             commands::stop_music,
-            commands::set_volume
+            commands::set_volume,
+            commands::get_fade_settings,
+            commands::set_fade_settings,
+            commands::get_sound_settings,
+            commands::set_sound_settings,
+            commands::list_announcements,
+            commands::get_announcement_settings,
+            commands::set_announcement_settings,
+            commands::get_tick_settings,
+            commands::set_tick_settings,
+            commands::preview_sound,
+            commands::list_bundled_sounds,
+            commands::list_ambient_sounds,
+            commands::play_ambient,
+            commands::stop_ambient,
+            commands::set_ambient_volume
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                let app = window.app_handle();
-                let timer_manager = app.state::<TimerManager>();
-
-                // Check if timer is running
-                if let Some(status) = timer_manager.get_status() {
-                    if status.is_running {
-                        // Prevent closing immediately
-                        api.prevent_close();
-
-                        use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
-                        
-                        let _ = app.dialog()
-                            .message("A Pomodoro session is currently running.\nPlease stop the timer before exiting.")
-                            .title("Session Active")
-                            .kind(MessageDialogKind::Warning)
-                            .show(|_| {});
-                    }
+                if window.label() == mini::LABEL {
+                    mini::remember_position(window);
+                } else {
+                    lifecycle::handle_close_requested(window, api);
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                lifecycle::on_exit(app);
+            }
+        });
 }
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::UNIX_EPOCH;
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use tauri::{AppHandle, Manager};
+use crate::error::GaweError;
+use crate::events::{self, GaweEvent};
+use crate::playlist;
+use crate::settings;
+
+/// Scanned tags live in the app's cache dir; only the folder list is a setting.
+const CACHE_FILE: &str = "library.json";
+/// Playlist sources and task audio can name a library track as `library:<id>`.
+pub const TRACK_PREFIX: &str = "library:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Track {
+    /// Derived from the path, so it survives rescans and restarts.
+    pub id: String,
+    pub path: String,
+    /// Tag title, or the file name when the file has none.
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration_ms: Option<u64>,
+    /// File modification time in seconds; unchanged files aren't re-read.
+    pub modified: u64,
+}
+
+impl Track {
+    fn matches(&self, query: &str) -> bool {
+        [Some(&self.title), self.artist.as_ref(), self.album.as_ref(), Some(&self.path)]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(query))
+    }
+}
+
+#[derive(Default)]
+struct LibraryState {
+    folders: Vec<String>,
+    tracks: Vec<Track>,
+    scanning: bool,
+    /// Folders changed mid-scan; scan again once the current one finishes.
+    rescan_pending: bool,
+}
+
+pub struct LibraryManager {
+    state: Arc<Mutex<LibraryState>>,
+}
+
+impl Default for LibraryManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LibraryManager {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LibraryState::default())),
+        }
+    }
+
+    /// Restores the folder list and the cached tracks, then rescans in the
+    /// background to pick up changes made while the app was closed.
+    pub fn load(&self, app: &AppHandle) {
+        {
+            let mut guard = self.state.lock().unwrap();
+            guard.folders = settings::load(app, settings::LIBRARY_FOLDERS_KEY).unwrap_or_default();
+            guard.tracks = read_cache(app);
+        }
+        self.rescan(app.clone());
+    }
+
+    pub fn folders(&self) -> Vec<String> {
+        self.state.lock().unwrap().folders.clone()
+    }
+
+    /// Persists the folder list and rescans.
+    pub fn set_folders(&self, app: &AppHandle, folders: Vec<String>) -> Result<(), GaweError> {
+        settings::save(app, settings::LIBRARY_FOLDERS_KEY, &folders)?;
+        self.state.lock().unwrap().folders = folders;
+        self.rescan(app.clone());
+        Ok(())
+    }
+
+    /// Walks the folders on a background thread and emits `library-updated`
+    /// with the track count when done. A request during a scan is queued and
+    /// run right after it.
+    pub fn rescan(&self, app: AppHandle) {
+        let (mut folders, mut cached) = {
+            let mut guard = self.state.lock().unwrap();
+            if guard.scanning {
+                guard.rescan_pending = true;
+                return;
+            }
+            guard.scanning = true;
+            (guard.folders.clone(), guard.tracks.clone())
+        };
+
+        let state = self.state.clone();
+        thread::spawn(move || loop {
+            let tracks = scan(&folders, cached);
+            write_cache(&app, &tracks);
+            let count = tracks.len();
+            {
+                let mut guard = state.lock().unwrap();
+                guard.tracks = tracks;
+                if guard.rescan_pending {
+                    guard.rescan_pending = false;
+                    folders = guard.folders.clone();
+                    cached = guard.tracks.clone();
+                    continue;
+                }
+                guard.scanning = false;
+            }
+            events::emit(&app, GaweEvent::LibraryUpdated { tracks: count });
+            break;
+        });
+    }
+
+    pub fn list_tracks(&self) -> Vec<Track> {
+        self.state.lock().unwrap().tracks.clone()
+    }
+
+    /// Case-insensitive match on title, artist, album or path.
+    pub fn search(&self, query: &str) -> Vec<Track> {
+        let query = query.trim().to_lowercase();
+        let guard = self.state.lock().unwrap();
+        guard.tracks.iter().filter(|t| t.matches(&query)).cloned().collect()
+    }
+
+    /// Replaces `library:<id>` entries with the file paths they stand for.
+    /// Unknown ids are dropped; other sources pass through untouched.
+    pub fn resolve_sources(&self, sources: &[String]) -> Vec<String> {
+        let guard = self.state.lock().unwrap();
+        sources
+            .iter()
+            .filter_map(|source| match source.strip_prefix(TRACK_PREFIX) {
+                Some(id) => guard.tracks.iter().find(|t| t.id == id).map(|t| t.path.clone()),
+                None => Some(source.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Stable across runs and Rust versions, unlike `DefaultHasher` (FNV-1a).
+fn track_id(path: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in path.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn scan(folders: &[String], cached: Vec<Track>) -> Vec<Track> {
+    let mut cached: HashMap<String, Track> = cached.into_iter().map(|t| (t.path.clone(), t)).collect();
+    let mut files = Vec::new();
+    for folder in folders {
+        collect_files(Path::new(folder), &mut files);
+    }
+    files.sort();
+    files.dedup();
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let modified = modified_secs(&path)?;
+            let key = path.to_string_lossy().into_owned();
+            match cached.remove(&key) {
+                Some(track) if track.modified == modified => Some(track),
+                _ => Some(read_track(&path, modified)),
+            }
+        })
+        .collect()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        // file_type doesn't follow symlinks, so linked folders can't loop
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&path, files);
+        } else if playlist::is_supported(&path) {
+            files.push(path);
+        }
+    }
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+/// Reads tags and duration; files that can't be probed are still listed by name.
+fn read_track(path: &Path, modified: u64) -> Track {
+    let path_string = path.to_string_lossy().into_owned();
+    let mut track = Track {
+        id: track_id(&path_string),
+        title: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path_string.clone()),
+        path: path_string,
+        artist: None,
+        album: None,
+        duration_ms: None,
+        modified,
+    };
+
+    let Ok(file) = File::open(path) else {
+        return track;
+    };
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+    let Ok(mut probed) = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+    else {
+        return track;
+    };
+
+    // ID3 tags sit in front of the container; others are inside it
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_tags(&mut track, revision.tags());
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut track, revision.tags());
+    }
+
+    track.duration_ms = probed.format.default_track().and_then(|t| {
+        let params = &t.codec_params;
+        let time = params.time_base?.calc_time(params.n_frames?);
+        Some(time.seconds * 1000 + (time.frac * 1000.0) as u64)
+    });
+    track
+}
+
+fn apply_tags(track: &mut Track, tags: &[Tag]) {
+    for tag in tags {
+        let value = tag.value.to_string().trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => track.title = value,
+            Some(StandardTagKey::Artist) => track.artist = Some(value),
+            Some(StandardTagKey::Album) => track.album = Some(value),
+            _ => {}
+        }
+    }
+}
+
+fn cache_path(app: &AppHandle) -> Option<PathBuf> {
+    Some(app.path().app_cache_dir().ok()?.join(CACHE_FILE))
+}
+
+fn read_cache(app: &AppHandle) -> Vec<Track> {
+    cache_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(app: &AppHandle, tracks: &[Track]) {
+    let Some(path) = cache_path(app) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, serde_json::to_string(tracks).unwrap_or_default()));
+    if let Err(e) = result {
+        eprintln!("Failed to write library cache: {}", e);
+    }
+}
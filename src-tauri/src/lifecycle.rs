@@ -0,0 +1,70 @@
+use tauri::{AppHandle, CloseRequestApi, Manager, Window};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+use crate::mini;
+use crate::settings::{self, CloseBehavior};
+use crate::timer::TimerManager;
+use crate::tray;
+
+const SAVE_AND_QUIT: &str = "Save and Quit";
+const STOP_AND_QUIT: &str = "Stop and Quit";
+
+pub fn handle_close_requested(window: &Window, api: &CloseRequestApi) {
+    let app = window.app_handle();
+    let behavior: CloseBehavior = settings::load(app, settings::CLOSE_BEHAVIOR_KEY).unwrap_or_default();
+    api.prevent_close();
+
+    // Without a tray there would be no way to bring the window back, and with
+    // nothing loaded there's nothing to keep running
+    let session_loaded = app.state::<TimerManager>().get_status().is_some();
+    if behavior == CloseBehavior::MinimizeToTray && tray::exists(app) && session_loaded {
+        let _ = window.hide();
+    } else {
+        request_quit(app);
+    }
+}
+
+/// Quits, first asking whether to keep or stop a running session.
+/// Progress itself is written by `on_exit`.
+pub fn request_quit(app: &AppHandle) {
+    let timer = app.state::<TimerManager>();
+    if !timer.get_status().is_some_and(|s| s.is_running) {
+        app.exit(0);
+        return;
+    }
+
+    let handle = app.clone();
+    app.dialog()
+        .message("A Pomodoro session is currently running.\nSave its progress to pick it up next time, or stop it?")
+        .title("Session Active")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            SAVE_AND_QUIT.to_string(),
+            STOP_AND_QUIT.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show_with_result(move |result| {
+            let timer = handle.state::<TimerManager>();
+            // Backends report custom buttons either by label or as Yes/No
+            match result {
+                MessageDialogResult::Yes => timer.pause(),
+                MessageDialogResult::No => timer.stop(),
+                MessageDialogResult::Custom(label) if label == SAVE_AND_QUIT => timer.pause(),
+                MessageDialogResult::Custom(label) if label == STOP_AND_QUIT => timer.stop(),
+                _ => return,
+            }
+            handle.exit(0);
+        });
+}
+
+/// Persists whatever session is loaded so the next launch can restore it,
+/// along with where the mini timer sits, and takes down the control socket.
+pub fn on_exit(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(mini::LABEL) {
+        mini::remember_position(&window.as_ref().window());
+    }
+    if let Err(e) = app.state::<TimerManager>().save_progress(app) {
+        eprintln!("Failed to save session progress: {}", e);
+    }
+    #[cfg(unix)]
+    crate::ipc::remove_socket();
+}
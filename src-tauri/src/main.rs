@@ -2,5 +2,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `gawe status`, `gawe pause`, ... talk to the running instance instead of starting one
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = agent_trons_lib::cli::run(&args) {
+        std::process::exit(code);
+    }
     agent_trons_lib::run()
 }
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Window};
+use crate::settings;
+
+/// Window label; the frontend renders the mini timer instead of the app for it.
+pub const LABEL: &str = "mini";
+
+/// Where the mini timer was last left, in logical pixels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MiniWindowPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+const WIDTH: f64 = 260.0;
+const HEIGHT: f64 = 96.0;
+/// How much of the window must land on a monitor to count as reachable.
+const MIN_VISIBLE: f64 = 40.0;
+
+/// A monitor's bounds in logical pixels.
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl MiniWindowPosition {
+    /// Whether enough of the window would show on one of `monitors` to drag
+    /// it back, e.g. not after the monitor it was left on was unplugged.
+    fn is_reachable(&self, monitors: &[Bounds]) -> bool {
+        monitors.iter().any(|m| {
+            let visible_width = (self.x + WIDTH).min(m.x + m.width) - self.x.max(m.x);
+            let visible_height = (self.y + HEIGHT).min(m.y + m.height) - self.y.max(m.y);
+            visible_width >= MIN_VISIBLE && visible_height >= MIN_VISIBLE
+        })
+    }
+}
+
+fn monitor_bounds(app: &AppHandle) -> Vec<Bounds> {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let scale = monitor.scale_factor();
+            let position = monitor.position().to_logical::<f64>(scale);
+            let size = monitor.size().to_logical::<f64>(scale);
+            Bounds { x: position.x, y: position.y, width: size.width, height: size.height }
+        })
+        .collect()
+}
+
+/// Opens the mini timer where it was last left, or closes it if it is open.
+pub fn toggle(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        // Closing goes through CloseRequested, which remembers the position
+        return window.close();
+    }
+
+    let mut builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html".into()))
+        .title("GaWe")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible_on_all_workspaces(true);
+    // A frameless window opened off-screen couldn't be dragged back
+    let saved = settings::load::<MiniWindowPosition>(app, settings::MINI_WINDOW_POSITION_KEY)
+        .filter(|position| position.is_reachable(&monitor_bounds(app)));
+    match saved {
+        Some(position) => builder = builder.position(position.x, position.y),
+        None => builder = builder.center(),
+    }
+    builder.build()?;
+    Ok(())
+}
+
+/// Saves where the mini timer sits so it reopens there.
+pub fn remember_position(window: &Window) {
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+    let Ok(scale) = window.scale_factor() else {
+        return;
+    };
+    let position = position.to_logical::<f64>(scale);
+    let position = MiniWindowPosition { x: position.x, y: position.y };
+    if let Err(e) = settings::save(window.app_handle(), settings::MINI_WINDOW_POSITION_KEY, &position) {
+        eprintln!("Failed to save mini timer position: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAPTOP: Bounds = Bounds { x: 0.0, y: 0.0, width: 1440.0, height: 900.0 };
+    const EXTERNAL: Bounds = Bounds { x: 1440.0, y: 0.0, width: 1920.0, height: 1080.0 };
+
+    fn at(x: f64, y: f64) -> MiniWindowPosition {
+        MiniWindowPosition { x, y }
+    }
+
+    #[test]
+    fn on_screen_positions_are_kept() {
+        assert!(at(100.0, 100.0).is_reachable(&[LAPTOP]));
+        assert!(at(2000.0, 500.0).is_reachable(&[LAPTOP, EXTERNAL]));
+    }
+
+    #[test]
+    fn position_on_unplugged_monitor_is_dropped() {
+        assert!(!at(2000.0, 500.0).is_reachable(&[LAPTOP]));
+    }
+
+    #[test]
+    fn mostly_off_screen_is_dropped() {
+        assert!(!at(1420.0, 100.0).is_reachable(&[LAPTOP]));
+        assert!(!at(100.0, -80.0).is_reachable(&[LAPTOP]));
+        assert!(at(-200.0, 100.0).is_reachable(&[LAPTOP]));
+    }
+
+    #[test]
+    fn no_monitors_means_center() {
+        assert!(!at(0.0, 0.0).is_reachable(&[]));
+    }
+}
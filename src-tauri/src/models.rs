@@ -1,18 +1,33 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::ambient::AmbientSound;
+use crate::error::GaweError;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskType {
     Work,
     Break,
 }
 
+/// What should be audible while a task runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum TaskAudio {
+    Silence,
+    /// Files, folders or playlists, played as one queue.
+    Music { sources: Vec<String> },
+    Ambient { sound: AmbientSound },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
     pub name: String,
     pub duration_minutes: u32,
     pub task_type: TaskType,
+    /// Overrides the session's audio for this task's type.
+    #[serde(default)]
+    pub audio: Option<TaskAudio>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,4 +36,32 @@ pub struct Session {
     pub name: String,
     pub tasks: Vec<Task>,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub work_audio: Option<TaskAudio>,
+    #[serde(default)]
+    pub break_audio: Option<TaskAudio>,
+}
+
+impl Session {
+    /// The audio for the task at `index`: its own, else the session's default
+    /// for its type. `None` leaves whatever is playing alone.
+    pub fn audio_for_task(&self, index: usize) -> Option<&TaskAudio> {
+        let task = self.tasks.get(index)?;
+        task.audio.as_ref().or(match task.task_type {
+            TaskType::Work => self.work_audio.as_ref(),
+            TaskType::Break => self.break_audio.as_ref(),
+        })
+    }
+
+    /// Rejects sessions the timer can't run, e.g. hand-edited or from a link.
+    pub fn validate(&self) -> Result<(), GaweError> {
+        let invalid = |message: &str| Err(GaweError::InvalidSession { message: message.to_string() });
+        if self.tasks.is_empty() {
+            return invalid("it has no tasks");
+        }
+        if self.tasks.iter().any(|task| task.duration_minutes == 0) {
+            return invalid("every task needs at least one minute");
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,103 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use rodio::cpal::traits::HostTrait;
+use rodio::cpal::{self, StreamError};
+use rodio::{DeviceTrait, OutputStream, OutputStreamBuilder};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OutputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Output devices the system currently offers, in host order.
+pub fn list_devices() -> Vec<OutputDevice> {
+    let host = cpal::default_host();
+    let default = default_device_name();
+    let Ok(devices) = host.output_devices() else {
+        return Vec::new();
+    };
+    devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| OutputDevice { is_default: default.as_ref() == Some(&name), name })
+        .collect()
+}
+
+/// The devices on offer at one moment, taken off the audio thread since
+/// enumerating them can block for a while (notably on ALSA).
+#[derive(Debug, Clone, Default)]
+pub struct DeviceSnapshot {
+    pub default: Option<String>,
+    pub names: Vec<String>,
+}
+
+impl DeviceSnapshot {
+    pub fn take() -> DeviceSnapshot {
+        let names = cpal::default_host()
+            .output_devices()
+            .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+            .unwrap_or_default();
+        DeviceSnapshot { default: default_device_name(), names }
+    }
+}
+
+pub fn default_device_name() -> Option<String> {
+    cpal::default_host().default_output_device()?.name().ok()
+}
+
+fn find_device(name: &str) -> Option<cpal::Device> {
+    cpal::default_host()
+        .output_devices()
+        .ok()?
+        .find(|device| device.name().ok().as_deref() == Some(name))
+}
+
+/// An open output stream and the device it plays on.
+pub struct Output {
+    pub stream: OutputStream,
+    pub device: String,
+    /// Set by the stream's error callback once the device is gone.
+    lost: Arc<AtomicBool>,
+}
+
+impl Output {
+    /// Opens `preferred`, falling back to the system default when it is
+    /// `None`, missing or fails to open. `device` names what actually opened.
+    pub fn open(preferred: Option<&str>) -> Result<Output, String> {
+        if let Some(device) = preferred.and_then(find_device) {
+            match Self::open_device(device) {
+                Ok(output) => return Ok(output),
+                Err(e) => eprintln!("Failed to open {}, using the default output: {}", preferred.unwrap_or_default(), e),
+            }
+        }
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| "No default output device".to_string())?;
+        Self::open_device(device)
+    }
+
+    fn open_device(device: cpal::Device) -> Result<Output, String> {
+        let name = device.name().map_err(|e| e.to_string())?;
+
+        let lost = Arc::new(AtomicBool::new(false));
+        let lost_flag = lost.clone();
+        let mut stream = OutputStreamBuilder::from_device(device)
+            .map_err(|e| e.to_string())?
+            .with_error_callback(move |e| {
+                eprintln!("Audio stream error: {}", e);
+                if matches!(e, StreamError::DeviceNotAvailable) {
+                    lost_flag.store(true, Ordering::Relaxed);
+                }
+            })
+            .open_stream_or_fallback()
+            .map_err(|e| e.to_string())?;
+        // Streams are swapped on purpose when devices change; rodio would warn on every drop
+        stream.log_on_drop(false);
+
+        Ok(Output { stream, device: name, lost })
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Relaxed)
+    }
+}
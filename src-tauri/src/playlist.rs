@@ -0,0 +1,301 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Extensions the decoder is built with; anything else in a folder is skipped.
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "flac", "m4a", "aac"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RepeatMode {
+    Off,
+    One,
+    #[default]
+    All,
+}
+
+/// Expands a mix of audio files, folders and M3U/PLS playlists into the
+/// audio files they refer to, keeping the given order.
+pub fn expand(sources: &[String]) -> Vec<String> {
+    let mut tracks = Vec::new();
+    for source in sources {
+        let path = Path::new(source);
+        if path.is_dir() {
+            tracks.extend(read_directory(path));
+            continue;
+        }
+        match extension_of(path).as_deref() {
+            Some("m3u") | Some("m3u8") => tracks.extend(parse_m3u(path)),
+            Some("pls") => tracks.extend(parse_pls(path)),
+            _ => tracks.push(source.clone()),
+        }
+    }
+    tracks
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension().map(|ext| ext.to_string_lossy().to_lowercase())
+}
+
+pub fn is_supported(path: &Path) -> bool {
+    extension_of(path).is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn read_directory(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_supported(path))
+        .collect();
+    files.sort();
+    files.into_iter().map(|p| p.to_string_lossy().into_owned()).collect()
+}
+
+/// Playlist entries may be relative to the playlist file itself.
+fn resolve_entry(playlist: &Path, entry: &str) -> Option<String> {
+    let entry = entry.trim();
+    if entry.is_empty() || entry.contains("://") {
+        // Streams are not supported
+        return None;
+    }
+    let entry_path = Path::new(entry);
+    let resolved = if entry_path.is_absolute() {
+        entry_path.to_path_buf()
+    } else {
+        playlist.parent().unwrap_or(Path::new("")).join(entry_path)
+    };
+    Some(resolved.to_string_lossy().into_owned())
+}
+
+fn parse_m3u(path: &Path) -> Vec<String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}'))
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| resolve_entry(path, line))
+        .collect()
+}
+
+fn parse_pls(path: &Path) -> Vec<String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    // Entries are "FileN=path"; N gives the order, not the line position
+    let mut entries: Vec<(u32, String)> = contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let number = key.trim().strip_prefix("File")?.parse().ok()?;
+            Some((number, resolve_entry(path, value)?))
+        })
+        .collect();
+    entries.sort_by_key(|(number, _)| *number);
+    entries.into_iter().map(|(_, track)| track).collect()
+}
+
+pub struct Playlist {
+    tracks: Vec<String>,
+    /// Play order as indices into `tracks`; shuffled when shuffle is on.
+    order: Vec<usize>,
+    cursor: usize,
+    repeat: RepeatMode,
+    shuffle: bool,
+}
+
+impl Playlist {
+    pub fn new(tracks: Vec<String>, repeat: RepeatMode, shuffle: bool) -> Self {
+        let mut order: Vec<usize> = (0..tracks.len()).collect();
+        if shuffle {
+            order.shuffle(&mut rand::rng());
+        }
+        Self { tracks, order, cursor: 0, repeat, shuffle }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.order.get(self.cursor).map(|&i| self.tracks[i].as_str())
+    }
+
+    /// Index of the current track in the original (unshuffled) list.
+    pub fn current_index(&self) -> Option<usize> {
+        self.order.get(self.cursor).copied()
+    }
+
+    pub fn set_repeat(&mut self, repeat: RepeatMode) {
+        self.repeat = repeat;
+    }
+
+    /// Reorders the remaining tracks; the current track keeps playing and
+    /// becomes the first of the new order.
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+        let current = self.current_index();
+        self.order = (0..self.tracks.len()).collect();
+        if shuffle {
+            self.order.shuffle(&mut rand::rng());
+            if let Some(current) = current {
+                let pos = self.order.iter().position(|&i| i == current).unwrap_or(0);
+                self.order.swap(0, pos);
+            }
+            self.cursor = 0;
+        } else {
+            self.cursor = current.unwrap_or(0);
+        }
+    }
+
+    /// The track to play after the current one ended by itself.
+    pub fn on_track_end(&mut self) -> Option<&str> {
+        match self.repeat {
+            RepeatMode::One => self.current(),
+            _ => self.skip_forward(),
+        }
+    }
+
+    pub fn skip_forward(&mut self) -> Option<&str> {
+        if self.cursor + 1 < self.order.len() {
+            self.cursor += 1;
+        } else if self.repeat == RepeatMode::Off || self.order.is_empty() {
+            return None;
+        } else {
+            if self.shuffle {
+                self.order.shuffle(&mut rand::rng());
+            }
+            self.cursor = 0;
+        }
+        self.current()
+    }
+
+    pub fn skip_back(&mut self) -> Option<&str> {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+        } else if self.repeat != RepeatMode::Off && !self.order.is_empty() {
+            self.cursor = self.order.len() - 1;
+        }
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gawe-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn playlist(repeat: RepeatMode, shuffle: bool) -> Playlist {
+        Playlist::new(vec!["a.mp3".to_string(), "b.mp3".to_string(), "c.mp3".to_string()], repeat, shuffle)
+    }
+
+    #[test]
+    fn repeat_all_wraps_both_ways() {
+        let mut list = playlist(RepeatMode::All, false);
+        assert_eq!(list.skip_back(), Some("c.mp3"));
+        assert_eq!(list.skip_forward(), Some("a.mp3"));
+        assert_eq!(list.skip_forward(), Some("b.mp3"));
+        assert_eq!(list.on_track_end(), Some("c.mp3"));
+    }
+
+    #[test]
+    fn repeat_off_stops_at_either_end() {
+        let mut list = playlist(RepeatMode::Off, false);
+        assert_eq!(list.skip_back(), Some("a.mp3"));
+        list.skip_forward();
+        list.skip_forward();
+        assert_eq!(list.on_track_end(), None);
+        assert_eq!(list.current(), Some("c.mp3"));
+    }
+
+    #[test]
+    fn repeat_one_replays_until_skipped() {
+        let mut list = playlist(RepeatMode::One, false);
+        assert_eq!(list.on_track_end(), Some("a.mp3"));
+        assert_eq!(list.skip_forward(), Some("b.mp3"));
+    }
+
+    #[test]
+    fn shuffle_plays_every_track_once_per_round() {
+        let mut list = playlist(RepeatMode::Off, true);
+        let mut played = vec![list.current().unwrap().to_string()];
+        while let Some(track) = list.skip_forward() {
+            played.push(track.to_string());
+        }
+        played.sort();
+        assert_eq!(played, vec!["a.mp3", "b.mp3", "c.mp3"]);
+    }
+
+    #[test]
+    fn toggling_shuffle_keeps_the_current_track() {
+        let mut list = playlist(RepeatMode::All, false);
+        list.skip_forward();
+        list.set_shuffle(true);
+        assert_eq!(list.current(), Some("b.mp3"));
+        list.set_shuffle(false);
+        assert_eq!(list.current(), Some("b.mp3"));
+        assert_eq!(list.skip_forward(), Some("c.mp3"));
+    }
+
+    #[test]
+    fn m3u_skips_comments_and_streams_and_resolves_relative_paths() {
+        let dir = scratch_dir("m3u");
+        let path = dir.join("focus.m3u");
+        fs::write(
+            &path,
+            "\u{feff}#EXTM3U\n#EXTINF:123,Rain\nrain.mp3\n\n/music/waves.ogg\nhttp://radio.example/stream\nsub/fire.flac\n",
+        )
+        .unwrap();
+        assert_eq!(
+            expand(&[path.to_string_lossy().into_owned()]),
+            vec![
+                dir.join("rain.mp3").to_string_lossy().into_owned(),
+                "/music/waves.ogg".to_string(),
+                dir.join("sub/fire.flac").to_string_lossy().into_owned(),
+            ],
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pls_orders_by_number_and_skips_malformed_entries() {
+        let dir = scratch_dir("pls");
+        let path = dir.join("focus.pls");
+        fs::write(
+            &path,
+            "[playlist]\nFile2=second.mp3\nTitle2=Second\nFile1=/music/first.mp3\nFileX=bad.mp3\nFile3=\nFile4\nNumberOfEntries=2\n",
+        )
+        .unwrap();
+        assert_eq!(
+            expand(&[path.to_string_lossy().into_owned()]),
+            vec!["/music/first.mp3".to_string(), dir.join("second.mp3").to_string_lossy().into_owned()],
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn folders_expand_to_sorted_audio_files() {
+        let dir = scratch_dir("folder");
+        for name in ["b.ogg", "a.MP3", "notes.txt"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(
+            expand(&[dir.to_string_lossy().into_owned()]),
+            vec![dir.join("a.MP3").to_string_lossy().into_owned(), dir.join("b.ogg").to_string_lossy().into_owned()],
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::mpsc;
+use std::thread;
+use tauri::{AppHandle, Manager};
+use crate::activity::ActivityManager;
+use crate::events::{self, GaweEvent};
+use crate::timer::TimerManager;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum PowerEvent {
+    Locked,
+    Unlocked,
+    Suspending,
+    Resumed,
+}
+
+/// Something that reports screen lock and suspend transitions.
+/// `run` blocks on its own thread and returns once the source is exhausted
+/// or the receiving side hangs up.
+pub trait PowerEventSource: Send + 'static {
+    fn run(self: Box<Self>, sender: mpsc::Sender<PowerEvent>);
+}
+
+/// Source fed by hand through a channel, used on platforms without a native
+/// backend and to drive the managers in tests.
+pub struct ChannelEventSource {
+    receiver: mpsc::Receiver<PowerEvent>,
+}
+
+impl ChannelEventSource {
+    pub fn new() -> (mpsc::Sender<PowerEvent>, Self) {
+        let (tx, rx) = mpsc::channel();
+        (tx, Self { receiver: rx })
+    }
+}
+
+impl PowerEventSource for ChannelEventSource {
+    fn run(self: Box<Self>, sender: mpsc::Sender<PowerEvent>) {
+        while let Ok(event) = self.receiver.recv() {
+            if sender.send(event).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Listens to systemd-logind on the system bus: `PrepareForSleep` on the
+/// manager and `Lock`/`Unlock` on the session this process belongs to.
+#[cfg(target_os = "linux")]
+pub struct LogindEventSource;
+
+#[cfg(target_os = "linux")]
+impl LogindEventSource {
+    const DESTINATION: &'static str = "org.freedesktop.login1";
+
+    fn listen(sender: mpsc::Sender<PowerEvent>) -> zbus::Result<()> {
+        use zbus::blocking::{Connection, Proxy};
+        use zbus::zvariant::OwnedObjectPath;
+
+        let conn = Connection::system()?;
+        let manager = Proxy::new(
+            &conn,
+            Self::DESTINATION,
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+        )?;
+
+        // "auto" resolves to the caller's session (or the user's display
+        // session); older logind versions only understand the PID lookup.
+        let session_path: OwnedObjectPath = manager
+            .call("GetSession", &("auto",))
+            .or_else(|_| manager.call("GetSessionByPID", &(std::process::id(),)))?;
+        let session = Proxy::new(
+            &conn,
+            Self::DESTINATION,
+            session_path,
+            "org.freedesktop.login1.Session",
+        )?;
+
+        let sleep_signals = manager.receive_signal("PrepareForSleep")?;
+        let lock_signals = session.receive_signal("Lock")?;
+        let unlock_signals = session.receive_signal("Unlock")?;
+
+        let sleep_sender = sender.clone();
+        thread::spawn(move || {
+            for message in sleep_signals {
+                // The argument is true before suspending and false after resuming
+                let Ok(starting) = message.body().deserialize::<bool>() else {
+                    continue;
+                };
+                let event = if starting { PowerEvent::Suspending } else { PowerEvent::Resumed };
+                if sleep_sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let lock_sender = sender.clone();
+        thread::spawn(move || {
+            for _ in lock_signals {
+                if lock_sender.send(PowerEvent::Locked).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in unlock_signals {
+            if sender.send(PowerEvent::Unlocked).is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl PowerEventSource for LogindEventSource {
+    fn run(self: Box<Self>, sender: mpsc::Sender<PowerEvent>) {
+        if let Err(e) = Self::listen(sender) {
+            eprintln!("Failed to watch logind for lock/suspend events: {}", e);
+        }
+    }
+}
+
+/// The native source for the current platform.
+pub fn default_source() -> Box<dyn PowerEventSource> {
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(LogindEventSource)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        // No native backend yet; the sender is dropped so monitoring ends immediately
+        let (_, source) = ChannelEventSource::new();
+        Box::new(source)
+    }
+}
+
+/// Runs `source` and dispatches every event to the activity and timer managers.
+pub fn start_monitoring(app: AppHandle, source: Box<dyn PowerEventSource>) {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || source.run(tx));
+
+    thread::spawn(move || {
+        while let Ok(event) = rx.recv() {
+            app.state::<ActivityManager>().handle_power_event(event);
+            app.state::<TimerManager>().handle_power_event(&app, event);
+            events::emit(&app, GaweEvent::Power { event });
+        }
+    });
+}
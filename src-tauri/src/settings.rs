@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use crate::announce::Announcement;
+use crate::distraction::AppRules;
+use crate::error::GaweError;
+use crate::hotkeys::HotkeyAction;
+use crate::models::{Session, TaskType};
+use crate::sounds::{SoundChoice, SoundEvent};
+use crate::tick::TickSample;
+
+/// Same file the frontend's `LazyStore('settings.json')` writes to; the plugin
+/// shares one in-memory copy between both sides.
+pub const STORE_PATH: &str = "settings.json";
+
+/// Saved sessions, written by the frontend's session store.
+pub const SESSIONS_STORE_PATH: &str = "sessions.json";
+const SESSIONS_KEY: &str = "sessions";
+
+pub const ACTIVITY_KEY: &str = "activity";
+pub const DISTRACTION_KEY: &str = "distraction";
+pub const FADES_KEY: &str = "audio_fades";
+pub const SOUNDS_KEY: &str = "sounds";
+pub const TICK_KEY: &str = "tick";
+pub const ANNOUNCEMENTS_KEY: &str = "announcements";
+pub const LIBRARY_FOLDERS_KEY: &str = "library_folders";
+/// Ids of the most recently loaded sessions, newest first.
+pub const RECENT_SESSIONS_KEY: &str = "recent_sessions";
+pub const CLOSE_BEHAVIOR_KEY: &str = "close_behavior";
+pub const HOTKEYS_KEY: &str = "hotkeys";
+pub const MINI_WINDOW_POSITION_KEY: &str = "mini_window_position";
+/// Session progress written on quit; `null` when nothing was in progress.
+pub const SAVED_PROGRESS_KEY: &str = "saved_progress";
+const MAX_RECENT_SESSIONS: usize = 5;
+/// Name of the chosen audio output; `null` follows the system default.
+pub const OUTPUT_DEVICE_KEY: &str = "audio_output_device";
+/// Where music files were left off, as `[path, milliseconds]` pairs with the
+/// most recently played last.
+pub const POSITIONS_KEY: &str = "playback_positions";
+/// Object the frontend used to keep every preference under.
+const LEGACY_FRONTEND_KEY: &str = "settings";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivitySettings {
+    pub enabled: bool,
+    pub threshold_seconds: u64,
+}
+
+impl Default for ActivitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_seconds: 300, // 5 minutes
+        }
+    }
+}
+
+/// Focused-app tracking during work tasks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DistractionSettings {
+    pub enabled: bool,
+    pub rules: AppRules,
+}
+
+/// What closing the main window does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CloseBehavior {
+    /// Hide the window and keep a loaded session going in the tray.
+    #[default]
+    MinimizeToTray,
+    /// Quit, asking first whether to keep a running session's progress.
+    Quit,
+}
+
+/// Global shortcut for each action, in accelerator form such as "CmdOrCtrl+Alt+P".
+/// An empty accelerator turns an action's shortcut off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeySettings {
+    pub bindings: BTreeMap<HotkeyAction, String>,
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            bindings: BTreeMap::from([
+                (HotkeyAction::StartPause, "CmdOrCtrl+Alt+P".to_string()),
+                (HotkeyAction::Skip, "CmdOrCtrl+Alt+N".to_string()),
+                (HotkeyAction::LogInterruption, "CmdOrCtrl+Alt+I".to_string()),
+                (HotkeyAction::PauseMusic, "CmdOrCtrl+Alt+M".to_string()),
+                (HotkeyAction::ToggleMiniTimer, "CmdOrCtrl+Alt+T".to_string()),
+            ]),
+        }
+    }
+}
+
+/// Music transition lengths in milliseconds; zero disables that fade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FadeSettings {
+    pub fade_in_ms: u64,
+    pub fade_out_ms: u64,
+    pub pause_fade_ms: u64,
+    pub crossfade_ms: u64,
+}
+
+impl Default for FadeSettings {
+    fn default() -> Self {
+        Self {
+            fade_in_ms: 1500,
+            fade_out_ms: 1500,
+            pause_fade_ms: 500,
+            crossfade_ms: 3000,
+        }
+    }
+}
+
+impl FadeSettings {
+    pub fn fade_in(&self) -> Duration {
+        Duration::from_millis(self.fade_in_ms)
+    }
+
+    pub fn fade_out(&self) -> Duration {
+        Duration::from_millis(self.fade_out_ms)
+    }
+
+    pub fn pause(&self) -> Duration {
+        Duration::from_millis(self.pause_fade_ms)
+    }
+
+    pub fn crossfade(&self) -> Duration {
+        Duration::from_millis(self.crossfade_ms)
+    }
+}
+
+/// Which sound plays for each session event, and how loud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundSettings {
+    pub volume: f32,
+    /// Music volume multiplier while a sound plays; 1.0 turns ducking off.
+    pub duck_level: f32,
+    pub work_start: SoundChoice,
+    pub break_start: SoundChoice,
+    pub session_finish: SoundChoice,
+    pub warning: SoundChoice,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            volume: 0.8,
+            duck_level: 0.3,
+            work_start: SoundChoice::Bundled { name: "bell.wav".to_string() },
+            break_start: SoundChoice::Bundled { name: "soft-chime.wav".to_string() },
+            session_finish: SoundChoice::Bundled { name: "gong.wav".to_string() },
+            warning: SoundChoice::Silent,
+        }
+    }
+}
+
+impl SoundSettings {
+    pub fn choice_for(&self, event: SoundEvent) -> &SoundChoice {
+        match event {
+            SoundEvent::WorkStart => &self.work_start,
+            SoundEvent::BreakStart => &self.break_start,
+            SoundEvent::SessionFinish => &self.session_finish,
+            SoundEvent::Warning => &self.warning,
+        }
+    }
+}
+
+/// Which timer moments are spoken aloud, and how loud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnnouncementSettings {
+    pub enabled: Vec<Announcement>,
+    pub volume: f32,
+}
+
+impl Default for AnnouncementSettings {
+    fn default() -> Self {
+        Self {
+            enabled: vec![
+                Announcement::WorkStart,
+                Announcement::BreakStart,
+                Announcement::SessionComplete,
+            ],
+            volume: 0.9,
+        }
+    }
+}
+
+/// Clock tick played every second of the timer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TickSettings {
+    /// Task types that tick; empty turns the tick off.
+    pub enabled_for: Vec<TaskType>,
+    pub volume: f32,
+    pub sample: TickSample,
+}
+
+impl Default for TickSettings {
+    fn default() -> Self {
+        Self {
+            enabled_for: Vec::new(),
+            volume: 0.3,
+            sample: TickSample::Mechanical,
+        }
+    }
+}
+
+/// Reads `key` from the settings store, or `None` if it is missing or no
+/// longer matches the expected shape.
+pub fn load<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
+    let store = app.store(STORE_PATH).ok()?;
+    let value = store.get(key)?;
+    serde_json::from_value(value).ok()
+}
+
+pub fn save<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), GaweError> {
+    let store = app.store(STORE_PATH).map_err(GaweError::io)?;
+    let value = serde_json::to_value(value).map_err(GaweError::io)?;
+    store.set(key, value);
+    store.save().map_err(GaweError::io)
+}
+
+pub fn load_sessions(app: &AppHandle) -> Vec<Session> {
+    app.store(SESSIONS_STORE_PATH)
+        .ok()
+        .and_then(|store| store.get(SESSIONS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// A saved session by id, or by the name shown in the app.
+pub fn find_session(app: &AppHandle, id_or_name: &str) -> Option<Session> {
+    load_sessions(app)
+        .into_iter()
+        .find(|s| s.id == id_or_name || s.name.eq_ignore_ascii_case(id_or_name))
+}
+
+/// Moves `session_id` to the front of the recent list.
+pub fn remember_recent_session(app: &AppHandle, session_id: &str) -> Result<(), GaweError> {
+    let mut recent: Vec<String> = load(app, RECENT_SESSIONS_KEY).unwrap_or_default();
+    recent.retain(|id| id != session_id);
+    recent.insert(0, session_id.to_string());
+    recent.truncate(MAX_RECENT_SESSIONS);
+    save(app, RECENT_SESSIONS_KEY, &recent)
+}
+
+/// Recently loaded sessions that still exist, newest first.
+pub fn recent_sessions(app: &AppHandle) -> Vec<Session> {
+    let recent: Vec<String> = load(app, RECENT_SESSIONS_KEY).unwrap_or_default();
+    let mut sessions = load_sessions(app);
+    recent
+        .iter()
+        .filter_map(|id| {
+            let index = sessions.iter().position(|s| &s.id == id)?;
+            Some(sessions.swap_remove(index))
+        })
+        .collect()
+}
+
+/// Saved shortcuts, with defaults filled in for actions added since they were saved.
+pub fn load_hotkeys(app: &AppHandle) -> HotkeySettings {
+    let mut hotkeys: HotkeySettings = load(app, HOTKEYS_KEY).unwrap_or_default();
+    for (action, accelerator) in HotkeySettings::default().bindings {
+        hotkeys.bindings.entry(action).or_insert(accelerator);
+    }
+    hotkeys
+}
+
+pub fn load_activity(app: &AppHandle) -> ActivitySettings {
+    if let Some(settings) = load(app, ACTIVITY_KEY) {
+        return settings;
+    }
+
+    // Carry over what the Settings page stored before the backend owned these
+    let mut settings = ActivitySettings::default();
+    if let Some(legacy) = load::<serde_json::Value>(app, LEGACY_FRONTEND_KEY) {
+        if let Some(enabled) = legacy.get("activityMonitoring").and_then(|v| v.as_bool()) {
+            settings.enabled = enabled;
+        }
+        if let Some(threshold) = legacy.get("activityThreshold").and_then(|v| v.as_u64()) {
+            settings.threshold_seconds = threshold;
+        }
+    }
+    settings
+}
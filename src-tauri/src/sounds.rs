@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager};
+
+/// Folder inside the app's resources holding the bundled notification sounds.
+const BUNDLED_DIR: &str = "tone";
+
+/// Moments in a session that can have their own sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SoundEvent {
+    WorkStart,
+    BreakStart,
+    SessionFinish,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SoundChoice {
+    /// The synthesized two-tone chime; always available.
+    Chime,
+    /// A file shipped in the app's `tone` resource folder.
+    Bundled { name: String },
+    /// Any audio file the user picked.
+    File { path: String },
+    Silent,
+}
+
+impl SoundChoice {
+    /// Where to load this sound from, or `None` for sounds that aren't files.
+    pub fn resolve(&self, app: &AppHandle) -> Option<PathBuf> {
+        match self {
+            SoundChoice::Bundled { name } => app
+                .path()
+                .resolve(PathBuf::from(BUNDLED_DIR).join(name), BaseDirectory::Resource)
+                .ok(),
+            SoundChoice::File { path } => Some(PathBuf::from(path)),
+            SoundChoice::Chime | SoundChoice::Silent => None,
+        }
+    }
+}
+
+/// Names of the sounds bundled with the app, for the picker.
+pub fn list_bundled(app: &AppHandle) -> Vec<String> {
+    app.path()
+        .resolve(BUNDLED_DIR, BaseDirectory::Resource)
+        .map(|dir| list_sounds(&dir))
+        .unwrap_or_default()
+}
+
+fn list_sounds(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| crate::playlist::is_supported(&entry.path()))
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::SoundSettings;
+
+    fn bundled_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("resources").join(BUNDLED_DIR)
+    }
+
+    #[test]
+    fn lists_bundled_tones() {
+        assert_eq!(
+            list_sounds(&bundled_dir()),
+            vec!["bell.wav", "ding.wav", "gong.wav", "soft-chime.wav", "wood-block.wav"],
+        );
+    }
+
+    #[test]
+    fn default_sounds_are_bundled() {
+        let bundled = list_sounds(&bundled_dir());
+        let defaults = SoundSettings::default();
+        for choice in [&defaults.work_start, &defaults.break_start, &defaults.session_finish, &defaults.warning] {
+            if let SoundChoice::Bundled { name } = choice {
+                assert!(bundled.contains(name), "{} is not bundled", name);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use serde_json::json;
+use crate::timer::{format_remaining, TimerUpdate};
+
+/// Output flavours for `gawe bar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarFormat {
+    /// One JSON object per line for a waybar `custom` module.
+    Waybar,
+    /// Plain text with `%{F}` colour tags.
+    Polybar,
+    /// full_text, short_text and color lines for an i3blocks block.
+    I3blocks,
+}
+
+impl BarFormat {
+    pub fn parse(name: &str) -> Option<BarFormat> {
+        match name {
+            "waybar" => Some(BarFormat::Waybar),
+            "polybar" => Some(BarFormat::Polybar),
+            "i3blocks" => Some(BarFormat::I3blocks),
+            _ => None,
+        }
+    }
+}
+
+/// What the bar shows; doubles as the CSS class for waybar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BarState {
+    Work,
+    Break,
+    Paused,
+    /// No session loaded, or the app isn't running.
+    Idle,
+}
+
+impl BarState {
+    fn of(status: Option<&TimerUpdate>) -> BarState {
+        match status {
+            None => BarState::Idle,
+            Some(s) if !s.is_running => BarState::Paused,
+            Some(s) if s.is_break => BarState::Break,
+            Some(_) => BarState::Work,
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            BarState::Work => "work",
+            BarState::Break => "break",
+            BarState::Paused => "paused",
+            BarState::Idle => "idle",
+        }
+    }
+
+    /// Colours for bars that can't be themed with CSS classes.
+    fn color(self) -> &'static str {
+        match self {
+            BarState::Work => "#E06C75",
+            BarState::Break => "#98C379",
+            BarState::Paused => "#E5C07B",
+            BarState::Idle => "#777777",
+        }
+    }
+}
+
+/// Renders a status snapshot in `format`. Idle renders as empty text, which
+/// waybar and i3blocks take as "hide the block".
+pub fn render(format: BarFormat, status: Option<&TimerUpdate>) -> String {
+    let state = BarState::of(status);
+    let (short, full, tooltip) = match status {
+        Some(s) => {
+            let remaining = format_remaining(s.remaining_seconds);
+            let tooltip = if s.is_running {
+                format!("{} — {} left", s.current_task_name, remaining)
+            } else {
+                format!("{} — paused at {}", s.current_task_name, remaining)
+            };
+            (remaining.clone(), format!("{} {}", remaining, s.current_task_name), tooltip)
+        }
+        None => (String::new(), String::new(), "GaWe is idle".to_string()),
+    };
+
+    match format {
+        BarFormat::Waybar => {
+            // Paused keeps the task type too, so themes can style "paused work" differently
+            let mut classes = vec![state.class()];
+            if let Some(s) = status.filter(|s| !s.is_running) {
+                classes.push(if s.is_break { "break" } else { "work" });
+            }
+            json!({
+                "text": short,
+                "alt": state.class(),
+                "tooltip": tooltip,
+                "class": classes,
+            })
+            .to_string()
+        }
+        BarFormat::Polybar if full.is_empty() => String::new(),
+        BarFormat::Polybar => format!("%{{F{}}}{}%{{F-}}", state.color(), full),
+        BarFormat::I3blocks => format!("{}\n{}\n{}", full, short, state.color()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use super::*;
+
+    fn status(is_running: bool, is_break: bool) -> TimerUpdate {
+        TimerUpdate {
+            remaining_seconds: 754,
+            current_task_index: 0,
+            is_running,
+            current_task_name: "Write report".to_string(),
+            is_break,
+            session_id: "deep-work".to_string(),
+            has_task_audio: false,
+        }
+    }
+
+    fn waybar(status: Option<&TimerUpdate>) -> Value {
+        serde_json::from_str(&render(BarFormat::Waybar, status)).unwrap()
+    }
+
+    #[test]
+    fn waybar_classes() {
+        assert_eq!(waybar(Some(&status(true, false)))["class"], json!(["work"]));
+        assert_eq!(waybar(Some(&status(true, true)))["class"], json!(["break"]));
+        assert_eq!(waybar(None)["class"], json!(["idle"]));
+    }
+
+    #[test]
+    fn waybar_paused_keeps_the_task_type() {
+        let paused_work = waybar(Some(&status(false, false)));
+        assert_eq!(paused_work["class"], json!(["paused", "work"]));
+        assert_eq!(paused_work["alt"], "paused");
+        assert_eq!(paused_work["tooltip"], "Write report — paused at 12:34");
+
+        assert_eq!(waybar(Some(&status(false, true)))["class"], json!(["paused", "break"]));
+    }
+
+    #[test]
+    fn waybar_idle_hides_the_block() {
+        let idle = waybar(None);
+        assert_eq!(idle["text"], "");
+        assert_eq!(idle["tooltip"], "GaWe is idle");
+    }
+
+    #[test]
+    fn polybar_colours_the_text() {
+        assert_eq!(
+            render(BarFormat::Polybar, Some(&status(true, true))),
+            "%{F#98C379}12:34 Write report%{F-}",
+        );
+        assert_eq!(render(BarFormat::Polybar, None), "");
+    }
+
+    #[test]
+    fn i3blocks_prints_full_short_and_color() {
+        assert_eq!(
+            render(BarFormat::I3blocks, Some(&status(true, false))),
+            "12:34 Write report\n12:34\n#E06C75",
+        );
+        assert_eq!(render(BarFormat::I3blocks, None), "\n\n#777777");
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(BarFormat::parse("i3blocks"), Some(BarFormat::I3blocks));
+        assert_eq!(BarFormat::parse("Waybar"), None);
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+use rand::Rng;
+use rodio::{ChannelCount, Sample, SampleRate, Source};
+use serde::{Deserialize, Serialize};
+
+const SAMPLE_RATE: SampleRate = 44_100;
+
+/// The sound played on every second of the timer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum TickSample {
+    /// Bright, short click of a wind-up clock.
+    Mechanical,
+    /// Muted, low tick.
+    Soft,
+    Woodblock,
+    /// Any short audio file the user picked.
+    File { path: String },
+}
+
+impl TickSample {
+    /// The generated click for built-in samples; files get the mechanical one
+    /// as a fallback for when they can't be decoded.
+    pub fn click(&self) -> Click {
+        match self {
+            TickSample::Mechanical | TickSample::File { .. } => Click::new(3200.0, 0.004, 0.6),
+            TickSample::Soft => Click::new(900.0, 0.008, 0.2),
+            TickSample::Woodblock => Click::new(1100.0, 0.025, 0.05),
+        }
+    }
+}
+
+/// A sine burst with a noisy attack and an exponential decay.
+#[derive(Clone)]
+pub struct Click {
+    frequency: f32,
+    /// Time for the level to fall to about a third, in seconds.
+    decay: f32,
+    /// Share of white noise mixed into the tone.
+    noise: f32,
+    sample: u32,
+    length: u32,
+}
+
+impl Click {
+    pub fn new(frequency: f32, decay: f32, noise: f32) -> Self {
+        Self {
+            frequency,
+            decay,
+            noise,
+            sample: 0,
+            // Past five time constants the tail is inaudible
+            length: (decay * 5.0 * SAMPLE_RATE as f32) as u32,
+        }
+    }
+}
+
+impl Iterator for Click {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.sample >= self.length {
+            return None;
+        }
+        let t = self.sample as f32 / SAMPLE_RATE as f32;
+        self.sample += 1;
+        let tone = (std::f32::consts::TAU * self.frequency * t).sin();
+        let noise = rand::rng().random_range(-1.0..1.0);
+        let envelope = (-t / self.decay).exp();
+        Some(envelope * ((1.0 - self.noise) * tone + self.noise * noise) * 0.5)
+    }
+}
+
+impl Source for Click {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.length as f32 / SAMPLE_RATE as f32))
+    }
+}
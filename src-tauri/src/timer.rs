@@ -1,11 +1,17 @@
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use crate::models::{Session, TaskType};
+use crate::models::{Session, Task, TaskType};
+use crate::announce::Announcement;
 use crate::audio::AudioManager;
+use crate::error::GaweError;
+use crate::events::{self, AdvanceReason, GaweEvent};
+use crate::power::PowerEvent;
+use crate::settings;
+use crate::sounds::SoundEvent;
 
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct TimerUpdate {
     pub remaining_seconds: u32,
     pub current_task_index: usize,
@@ -13,6 +19,26 @@ pub struct TimerUpdate {
     pub current_task_name: String,
     pub is_break: bool,
     pub session_id: String,
+    /// The backend picks the audio for this task; the frontend shouldn't start its own.
+    pub has_task_audio: bool,
+}
+
+/// "12:34", or "1:02:03" for long tasks.
+pub fn format_remaining(seconds: u32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Where a session stood when the app quit, restored paused on the next launch.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedProgress {
+    pub session: Session,
+    pub current_task_index: usize,
+    pub remaining_seconds: u32,
 }
 
 pub struct TimerState {
@@ -21,12 +47,93 @@ pub struct TimerState {
     pub remaining_seconds: u32,
     pub is_running: bool,
     pub handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Set when the timer was paused by a screen lock or suspend rather than the user.
+    pub paused_by_system: bool,
+    pub screen_locked: bool,
+    /// Set once the last task ends; the session stays loaded but reports no status.
+    pub finished: bool,
+}
+
+/// What a lock or suspend transition did to the timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerOutcome {
+    Paused,
+    /// The user is back at an unlocked screen after a system pause.
+    ResumePrompt,
+}
+
+impl TimerState {
+    /// Moves on to the next task with its full time, or marks the session
+    /// finished after the last one. Returns `false` once the session is over.
+    fn next_task(&mut self) -> bool {
+        let Some(session) = &self.session else {
+            return false;
+        };
+        if let Some(next) = session.tasks.get(self.current_task_index + 1) {
+            self.current_task_index += 1;
+            self.remaining_seconds = next.duration_minutes * 60;
+            true
+        } else {
+            self.is_running = false;
+            self.remaining_seconds = 0;
+            self.finished = true;
+            false
+        }
+    }
+
+    /// What `save_progress` writes: the loaded session unless it already finished.
+    fn progress(&self) -> Option<SavedProgress> {
+        if self.finished {
+            return None;
+        }
+        self.session.as_ref().map(|session| SavedProgress {
+            session: session.clone(),
+            current_task_index: self.current_task_index,
+            remaining_seconds: self.remaining_seconds,
+        })
+    }
+
+    fn apply_power_event(&mut self, event: PowerEvent) -> Option<PowerOutcome> {
+        match event {
+            PowerEvent::Locked | PowerEvent::Suspending => {
+                if event == PowerEvent::Locked {
+                    self.screen_locked = true;
+                }
+                if !self.is_running {
+                    return None;
+                }
+                self.is_running = false;
+                self.paused_by_system = true;
+                if let Some(handle) = self.handle.take() {
+                    handle.abort();
+                }
+                Some(PowerOutcome::Paused)
+            }
+            PowerEvent::Unlocked | PowerEvent::Resumed => {
+                if event == PowerEvent::Unlocked {
+                    self.screen_locked = false;
+                }
+                // Resuming from suspend usually lands on the lock screen; wait for the unlock
+                if !self.paused_by_system || self.screen_locked {
+                    return None;
+                }
+                self.paused_by_system = false;
+                Some(PowerOutcome::ResumePrompt)
+            }
+        }
+    }
 }
 
 pub struct TimerManager {
     pub state: Arc<Mutex<TimerState>>,
 }
 
+impl Default for TimerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimerManager {
     pub fn new() -> Self {
         Self {
@@ -36,6 +143,9 @@ impl TimerManager {
                 remaining_seconds: 0,
                 is_running: false,
                 handle: None,
+                paused_by_system: false,
+                screen_locked: false,
+                finished: false,
             })),
         }
     }
@@ -51,20 +161,67 @@ impl TimerManager {
         }
         guard.session = Some(session);
         guard.is_running = false;
+        guard.paused_by_system = false;
+        guard.finished = false;
         if let Some(handle) = guard.handle.take() {
             handle.abort();
         }
     }
 
-    pub fn start(&self, app: AppHandle) {
+    /// Unloads the session entirely.
+    pub fn stop(&self) {
+        let mut guard = self.state.lock().unwrap();
+        guard.session = None;
+        guard.current_task_index = 0;
+        guard.remaining_seconds = 0;
+        guard.is_running = false;
+        guard.paused_by_system = false;
+        guard.finished = false;
+        if let Some(handle) = guard.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Writes the loaded session's progress to the store, or clears it when
+    /// nothing is loaded or the session already finished.
+    pub fn save_progress(&self, app: &AppHandle) -> Result<(), GaweError> {
+        let progress = self.state.lock().unwrap().progress();
+        settings::save(app, settings::SAVED_PROGRESS_KEY, &progress)
+    }
+
+    /// Loads progress saved by the last run, paused where it was left.
+    pub fn restore_progress(&self, app: &AppHandle) {
+        let Some(progress) = settings::load::<Option<SavedProgress>>(app, settings::SAVED_PROGRESS_KEY).flatten() else {
+            return;
+        };
+        if progress.current_task_index >= progress.session.tasks.len() {
+            return;
+        }
+        let mut guard = self.state.lock().unwrap();
+        guard.session = Some(progress.session);
+        guard.current_task_index = progress.current_task_index;
+        guard.remaining_seconds = progress.remaining_seconds;
+        guard.is_running = false;
+        guard.finished = false;
+    }
+
+    pub fn start(&self, app: AppHandle) -> Result<(), GaweError> {
         let state = self.state.clone();
         let mut guard = state.lock().unwrap();
-        
-        if guard.is_running || guard.session.is_none() {
-            return;
+
+        if guard.session.is_none() || guard.finished {
+            return Err(GaweError::NoSessionLoaded);
+        }
+        if guard.is_running {
+            return Err(GaweError::AlreadyRunning);
         }
         
         guard.is_running = true;
+        guard.paused_by_system = false;
+
+        if let Some(audio) = guard.session.as_ref().unwrap().audio_for_task(guard.current_task_index) {
+            app.state::<AudioManager>().play_task_audio(audio.clone());
+        }
         
         // Cancel any existing task just in case
         if let Some(handle) = guard.handle.take() {
@@ -86,66 +243,97 @@ impl TimerManager {
                 if guard.remaining_seconds > 0 {
                     guard.remaining_seconds -= 1;
                     
-                    let session = guard.session.as_ref().unwrap();
-                    let current_task = &session.tasks[guard.current_task_index];
-                    
-                    let update = TimerUpdate {
-                        remaining_seconds: guard.remaining_seconds,
-                        current_task_index: guard.current_task_index,
-                        is_running: true,
-                        current_task_name: current_task.name.clone(),
-                        is_break: matches!(current_task.task_type, TaskType::Break),
-                        session_id: session.id.clone(),
-                    };
-                    let _ = app_clone.emit("timer-update", update);
-                } else {
-                    // Task finished
-                    // Move to next task
-                    let session_len = guard.session.as_ref().unwrap().tasks.len();
-                    if guard.current_task_index + 1 < session_len {
-                        guard.current_task_index += 1;
-                        
-                        let (next_task_name, duration) = {
-                            let session = guard.session.as_ref().unwrap();
-                            let next_task = &session.tasks[guard.current_task_index];
-                            (next_task.name.clone(), next_task.duration_minutes)
-                        };
-                        
-                        guard.remaining_seconds = duration * 60;
-                        
-                        // Notify task change
-                        let _ = app_clone.emit("task-changed", next_task_name.clone());
-                        let _ = app_clone.notification()
-                            .builder()
-                            .title("Task Finished")
-                            .body(format!("Next: {}", next_task_name))
-                            .show();
-                        
-                        // Ring the bell
-                        let audio_manager = app_clone.state::<AudioManager>();
-                        audio_manager.play_bell();
-
-                    } else {
-                        // Session finished
-                        guard.is_running = false;
-                        let _ = app_clone.emit("session-finished", ());
-                        let _ = app_clone.notification()
-                            .builder()
-                            .title("Session Finished")
-                            .body("All tasks completed!")
-                            .show();
-                        
-                        // Ring the bell for session finish too
-                        let audio_manager = app_clone.state::<AudioManager>();
-                        audio_manager.play_bell();
-                        
-                        break;
+                    if let Some(update) = TimerManager::status_of(&guard) {
+                        events::emit(&app_clone, GaweEvent::TimerUpdate(update));
+                    }
+                    let task = &guard.session.as_ref().unwrap().tasks[guard.current_task_index];
+                    let audio_manager = app_clone.state::<AudioManager>();
+                    audio_manager.tick(task.task_type);
+                    if let Some(warning) = Announcement::for_remaining(guard.remaining_seconds) {
+                        audio_manager.announce(warning);
                     }
+                } else if !TimerManager::advance(&app_clone, &mut guard, AdvanceReason::Completed) {
+                    break;
                 }
             }
         });
         
         guard.handle = Some(handle);
+        Ok(())
+    }
+
+    /// Moves on to the next task, or finishes the session after the last one.
+    /// Sounds only play while the timer runs; a paused timer leaves them to `start`.
+    /// Returns `false` once the session is over.
+    fn advance(app: &AppHandle, state: &mut TimerState, reason: AdvanceReason) -> bool {
+        let audible = state.is_running;
+        if state.next_task() {
+            let session = state.session.as_ref().unwrap();
+            let next_task = &session.tasks[state.current_task_index];
+            let next_task_name = next_task.name.clone();
+            let next_is_break = matches!(next_task.task_type, TaskType::Break);
+            let next_audio = session.audio_for_task(state.current_task_index).cloned();
+
+            // Notify task change
+            events::emit(app, GaweEvent::TaskChanged {
+                session_id: session.id.clone(),
+                task_id: next_task.id.clone(),
+                task_name: next_task_name.clone(),
+                task_index: state.current_task_index,
+                is_break: next_is_break,
+                reason,
+            });
+            let _ = app.notification()
+                .builder()
+                .title("Task Finished")
+                .body(format!("Next: {}", next_task_name))
+                .show();
+            
+            // Switch soundscape, then ring the bell for the kind of task that starts
+            if audible {
+                let audio_manager = app.state::<AudioManager>();
+                if let Some(audio) = next_audio {
+                    audio_manager.play_task_audio(audio);
+                }
+                audio_manager.play_sound(if next_is_break { SoundEvent::BreakStart } else { SoundEvent::WorkStart });
+                audio_manager.announce(if next_is_break { Announcement::BreakStart } else { Announcement::WorkStart });
+            }
+            true
+        } else {
+            // Session finished
+            let session_id = state.session.as_ref().unwrap().id.clone();
+            events::emit(app, GaweEvent::SessionFinished { session_id, reason });
+            let _ = app.notification()
+                .builder()
+                .title("Session Finished")
+                .body("All tasks completed!")
+                .show();
+            
+            // Ring the bell for session finish too
+            if audible {
+                let audio_manager = app.state::<AudioManager>();
+                audio_manager.play_sound(SoundEvent::SessionFinish);
+                audio_manager.announce(Announcement::SessionComplete);
+            }
+            false
+        }
+    }
+
+    /// Ends the current task early, keeping the timer running or paused as it was.
+    pub fn skip(&self, app: &AppHandle) -> Result<(), GaweError> {
+        let mut guard = self.state.lock().unwrap();
+        if guard.session.is_none() || guard.finished {
+            return Err(GaweError::NoSessionLoaded);
+        }
+        if !Self::advance(app, &mut guard, AdvanceReason::Skipped) {
+            if let Some(handle) = guard.handle.take() {
+                handle.abort();
+            }
+        }
+        if let Some(update) = Self::status_of(&guard) {
+            events::emit(app, GaweEvent::TimerUpdate(update));
+        }
+        Ok(())
     }
 
     pub fn pause(&self) {
@@ -156,21 +344,209 @@ impl TimerManager {
         }
     }
 
+    /// Pauses a running timer along with its music, or starts/resumes it.
+    pub fn toggle(&self, app: AppHandle) -> Result<(), GaweError> {
+        if self.get_status().is_some_and(|s| s.is_running) {
+            self.pause();
+            app.state::<AudioManager>().pause_if_playing();
+        } else {
+            self.start(app.clone())?;
+        }
+        if let Some(update) = self.get_status() {
+            events::emit(&app, GaweEvent::TimerUpdate(update));
+        }
+        Ok(())
+    }
+
+    /// Auto-pauses on lock/suspend so time away isn't counted, and asks the
+    /// user to resume once they are back at an unlocked screen.
+    pub fn handle_power_event(&self, app: &AppHandle, event: PowerEvent) {
+        let mut guard = self.state.lock().unwrap();
+        let Some(outcome) = guard.apply_power_event(event) else {
+            return;
+        };
+        let Some(update) = Self::status_of(&guard) else {
+            return;
+        };
+        match outcome {
+            PowerOutcome::Paused => events::emit(app, GaweEvent::TimerUpdate(update)),
+            PowerOutcome::ResumePrompt => {
+                events::emit(app, GaweEvent::ResumePrompt(update));
+                let _ = app.notification()
+                    .builder()
+                    .title("Welcome Back")
+                    .body("Your session was paused while you were away. Resume when ready.")
+                    .show();
+            }
+        }
+    }
+
+    /// The session id and task currently being timed, if the timer is running.
+    pub fn running_task(&self) -> Option<(String, Task)> {
+        let guard = self.state.lock().unwrap();
+        if !guard.is_running {
+            return None;
+        }
+        let session = guard.session.as_ref()?;
+        let task = session.tasks.get(guard.current_task_index)?;
+        Some((session.id.clone(), task.clone()))
+    }
+
     pub fn get_status(&self) -> Option<TimerUpdate> {
         let guard = self.state.lock().unwrap();
-        if let Some(session) = &guard.session {
-            if guard.current_task_index < session.tasks.len() {
-                let current_task = &session.tasks[guard.current_task_index];
+        Self::status_of(&guard)
+    }
+
+    fn status_of(state: &TimerState) -> Option<TimerUpdate> {
+        if state.finished {
+            return None;
+        }
+        if let Some(session) = &state.session {
+            if state.current_task_index < session.tasks.len() {
+                let current_task = &session.tasks[state.current_task_index];
                 return Some(TimerUpdate {
-                    remaining_seconds: guard.remaining_seconds,
-                    current_task_index: guard.current_task_index,
-                    is_running: guard.is_running,
+                    remaining_seconds: state.remaining_seconds,
+                    current_task_index: state.current_task_index,
+                    is_running: state.is_running,
                     current_task_name: current_task.name.clone(),
                     is_break: matches!(current_task.task_type, TaskType::Break),
                     session_id: session.id.clone(),
+                    has_task_audio: session.audio_for_task(state.current_task_index).is_some(),
                 });
             }
         }
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running_state() -> TimerState {
+        let session = Session {
+            id: "deep-work".to_string(),
+            name: "Deep Work".to_string(),
+            tasks: vec![Task {
+                id: "focus".to_string(),
+                name: "Focus".to_string(),
+                duration_minutes: 25,
+                task_type: TaskType::Work,
+                audio: None,
+            }],
+            created_at: chrono::Utc::now(),
+            work_audio: None,
+            break_audio: None,
+        };
+        TimerState {
+            session: Some(session),
+            current_task_index: 0,
+            remaining_seconds: 25 * 60,
+            is_running: true,
+            handle: None,
+            paused_by_system: false,
+            screen_locked: false,
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn skipping_last_task_finishes_session() {
+        let mut state = running_state();
+        assert!(!state.next_task());
+        assert!(state.finished);
+        assert!(!state.is_running);
+        assert_eq!(state.remaining_seconds, 0);
+        assert!(TimerManager::status_of(&state).is_none());
+    }
+
+    #[test]
+    fn paused_session_is_saved() {
+        let mut state = running_state();
+        state.is_running = false;
+        state.remaining_seconds = 600;
+        let progress = state.progress().unwrap();
+        assert_eq!(progress.session.id, "deep-work");
+        assert_eq!(progress.current_task_index, 0);
+        assert_eq!(progress.remaining_seconds, 600);
+    }
+
+    #[test]
+    fn session_is_not_saved_after_last_task_is_skipped() {
+        let mut state = running_state();
+        state.is_running = false;
+        state.next_task();
+        assert!(state.progress().is_none());
+    }
+
+    #[test]
+    fn skipping_moves_to_next_task_with_full_time() {
+        let mut state = running_state();
+        let session = state.session.as_mut().unwrap();
+        session.tasks.push(Task {
+            id: "rest".to_string(),
+            name: "Rest".to_string(),
+            duration_minutes: 5,
+            task_type: TaskType::Break,
+            audio: None,
+        });
+        state.remaining_seconds = 60;
+        assert!(state.next_task());
+        assert!(!state.finished);
+        assert_eq!(state.current_task_index, 1);
+        assert_eq!(state.remaining_seconds, 5 * 60);
+    }
+
+    fn drive(state: &mut TimerState, events: &[PowerEvent]) -> Vec<Option<PowerOutcome>> {
+        events.iter().map(|&event| state.apply_power_event(event)).collect()
+    }
+
+    #[test]
+    fn lock_pauses_and_unlock_prompts() {
+        let mut state = running_state();
+        let outcomes = drive(&mut state, &[PowerEvent::Locked, PowerEvent::Unlocked]);
+        assert_eq!(outcomes, vec![Some(PowerOutcome::Paused), Some(PowerOutcome::ResumePrompt)]);
+        assert!(!state.is_running);
+        assert!(!state.paused_by_system);
+        assert_eq!(state.remaining_seconds, 25 * 60);
+    }
+
+    #[test]
+    fn resume_from_suspend_waits_for_unlock() {
+        let mut state = running_state();
+        let outcomes = drive(
+            &mut state,
+            &[PowerEvent::Suspending, PowerEvent::Locked, PowerEvent::Resumed, PowerEvent::Unlocked],
+        );
+        assert_eq!(
+            outcomes,
+            vec![Some(PowerOutcome::Paused), None, None, Some(PowerOutcome::ResumePrompt)],
+        );
+    }
+
+    #[test]
+    fn resume_without_lock_prompts_right_away() {
+        let mut state = running_state();
+        let outcomes = drive(&mut state, &[PowerEvent::Suspending, PowerEvent::Resumed]);
+        assert_eq!(outcomes, vec![Some(PowerOutcome::Paused), Some(PowerOutcome::ResumePrompt)]);
+    }
+
+    #[test]
+    fn timer_paused_by_user_is_left_alone() {
+        let mut state = running_state();
+        state.is_running = false;
+        let outcomes = drive(&mut state, &[PowerEvent::Locked, PowerEvent::Unlocked]);
+        assert_eq!(outcomes, vec![None, None]);
+        assert!(!state.is_running);
+    }
+
+    #[test]
+    fn prompts_only_once() {
+        let mut state = running_state();
+        let outcomes = drive(
+            &mut state,
+            &[PowerEvent::Locked, PowerEvent::Unlocked, PowerEvent::Locked, PowerEvent::Unlocked],
+        );
+        assert_eq!(outcomes, vec![Some(PowerOutcome::Paused), Some(PowerOutcome::ResumePrompt), None, None]);
+    }
+}
@@ -0,0 +1,174 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Listener, Manager, Wry};
+use crate::audio::AudioManager;
+use crate::lifecycle;
+use crate::mini;
+use crate::settings;
+use crate::commands;
+use crate::timer::{format_remaining, TimerManager, TimerUpdate};
+
+const TRAY_ID: &str = "main";
+/// Menu ids of recent sessions carry the session id after this prefix.
+const RECENT_PREFIX: &str = "recent:";
+
+/// Creates the tray icon and keeps its countdown and menu current.
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("GaWe")
+        .menu(&build_menu(app, false)?)
+        .on_menu_event(|app, event| handle_menu(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    start_updating(app.clone());
+    Ok(())
+}
+
+fn build_menu(app: &AppHandle, is_running: bool) -> tauri::Result<Menu<Wry>> {
+    let start_pause = MenuItem::with_id(app, "start-pause", if is_running { "Pause" } else { "Start" }, true, None::<&str>)?;
+    let skip = MenuItem::with_id(app, "skip", "Skip Task", true, None::<&str>)?;
+    let stop_music = MenuItem::with_id(app, "stop-music", "Stop Music", true, None::<&str>)?;
+    let mini = MenuItem::with_id(app, "mini", "Mini Timer", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Open GaWe", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit GaWe", true, None::<&str>)?;
+
+    let recent = Submenu::with_id(app, "recent", "Quick Start", true)?;
+    let sessions = settings::recent_sessions(app);
+    if sessions.is_empty() {
+        recent.append(&MenuItem::with_id(app, "recent-none", "No recent sessions", false, None::<&str>)?)?;
+    }
+    for session in sessions {
+        let id = format!("{}{}", RECENT_PREFIX, session.id);
+        recent.append(&MenuItem::with_id(app, id, session.name, true, None::<&str>)?)?;
+    }
+
+    Menu::with_items(app, &[
+        &start_pause,
+        &skip,
+        &stop_music,
+        &PredefinedMenuItem::separator(app)?,
+        &recent,
+        &PredefinedMenuItem::separator(app)?,
+        &mini,
+        &show,
+        &quit,
+    ])
+}
+
+fn handle_menu(app: &AppHandle, id: &str) {
+    let timer = app.state::<TimerManager>();
+    match id {
+        "start-pause" => {
+            if let Err(e) = timer.toggle(app.clone()) {
+                eprintln!("Failed to start the timer: {}", e);
+            }
+        }
+        "skip" => {
+            if let Err(e) = timer.skip(app) {
+                eprintln!("Failed to skip task: {}", e);
+            }
+        }
+        "stop-music" => app.state::<AudioManager>().stop(),
+        "mini" => {
+            if let Err(e) = mini::toggle(app) {
+                eprintln!("Failed to toggle mini timer: {}", e);
+            }
+        }
+        "show" => show_main_window(app),
+        "quit" => lifecycle::request_quit(app),
+        _ => {
+            if let Some(session_id) = id.strip_prefix(RECENT_PREFIX) {
+                quick_start(app, session_id);
+            }
+        }
+    }
+}
+
+/// Starts a saved session and brings up the focus view.
+fn quick_start(app: &AppHandle, session_id: &str) {
+    match commands::start_session(app.clone(), session_id.to_string(), app.state()) {
+        Ok(()) => show_main_window(app),
+        Err(e) => eprintln!("Failed to quick-start session: {}", e),
+    }
+}
+
+pub fn exists(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn tooltip_for(status: Option<&TimerUpdate>) -> String {
+    match status {
+        Some(s) if s.is_running => format!("{} — {} left", s.current_task_name, format_remaining(s.remaining_seconds)),
+        Some(s) => format!("{} — paused at {}", s.current_task_name, format_remaining(s.remaining_seconds)),
+        None => "GaWe".to_string(),
+    }
+}
+
+/// Refreshes the countdown once a second. The menu is only rebuilt when the
+/// start/pause label flips or the store reports a change to the sessions or
+/// the recent list, rather than reading them on every tick.
+fn start_updating(app: AppHandle) {
+    let recent_changed = Arc::new(AtomicBool::new(false));
+    let flag = recent_changed.clone();
+    app.listen("store://change", move |event| {
+        let Ok(change) = serde_json::from_str::<StoreChange>(event.payload()) else {
+            return;
+        };
+        if change.key == settings::RECENT_SESSIONS_KEY || change.path.ends_with(settings::SESSIONS_STORE_PATH) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    });
+
+    thread::spawn(move || {
+        let mut shown_running = false;
+        loop {
+            thread::sleep(Duration::from_secs(1));
+            let Some(tray) = app.tray_by_id(TRAY_ID) else {
+                continue;
+            };
+
+            let status = app.state::<TimerManager>().get_status();
+            let title = status.as_ref().filter(|s| s.is_running).map(|s| format_remaining(s.remaining_seconds));
+            let _ = tray.set_title(title);
+            let _ = tray.set_tooltip(Some(tooltip_for(status.as_ref())));
+
+            let is_running = status.as_ref().is_some_and(|s| s.is_running);
+            if is_running == shown_running && !recent_changed.swap(false, Ordering::Relaxed) {
+                continue;
+            }
+            match build_menu(&app, is_running) {
+                Ok(menu) => {
+                    let _ = tray.set_menu(Some(menu));
+                    shown_running = is_running;
+                }
+                Err(e) => {
+                    eprintln!("Failed to rebuild tray menu: {}", e);
+                    recent_changed.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    });
+}
+
+/// The parts of the store plugin's `store://change` payload the tray needs.
+#[derive(serde::Deserialize)]
+struct StoreChange {
+    path: PathBuf,
+    key: String,
+}
@@ -1,11 +1,14 @@
 import { useEffect } from 'react';
 import { HashRouter as Router, Routes, Route } from 'react-router-dom';
+import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { Home } from './pages/Home';
 import { Config } from './pages/Config';
 import { Focus } from './pages/Focus';
 import { Settings } from './pages/Settings';
 import { useSessionStore } from './stores/sessionStore';
+import { useSettingsStore } from './stores/settingsStore';
+import { ActivitySettings, TimerUpdate } from './types';
 import './index.css';
 
 function App() {
@@ -13,6 +16,10 @@ function App() {
 
   useEffect(() => {
     loadSessions();
+    // A session saved on the last quit is restored paused; pick it up where it was
+    invoke<TimerUpdate | null>('get_timer_status').then((status) => {
+      if (status) window.location.hash = '#/focus';
+    }).catch(console.error);
   }, []);
 
   useEffect(() => {
@@ -26,6 +33,30 @@ function App() {
     };
   }, []);
 
+  // Sessions quick-started from the tray open straight into the focus view
+  useEffect(() => {
+    const unlisten = listen('session-started', () => {
+      window.location.hash = '#/focus';
+    });
+
+    return () => {
+      unlisten.then(f => f());
+    };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<ActivitySettings>('activity-settings-changed', (event) => {
+      useSettingsStore.setState({
+        activityMonitoring: event.payload.enabled,
+        activityThreshold: event.payload.threshold_seconds,
+      });
+    });
+
+    return () => {
+      unlisten.then(f => f());
+    };
+  }, []);
+
   return (
     <Router>
       <Routes>
@@ -0,0 +1,29 @@
+import { AudioError, GaweError } from '../types';
+
+export const describeAudioError = (err: AudioError) => {
+  switch (err.kind) {
+    case 'FileNotFound': return `The music file could not be found:\n${err.path}`;
+    case 'UnsupportedFormat': return `This audio format is not supported:\n${err.path}`;
+    case 'Io': return `The music file could not be read: ${err.message}`;
+    case 'EmptyPlaylist': return 'No playable audio files were found.';
+    case 'NotPlaying': return 'No track is loaded.';
+    case 'SeekFailed': return `Could not jump within the track: ${err.message}`;
+    case 'NoOutputDevice': return `No audio output device is available: ${err.message}`;
+    case 'DeviceNotFound': return `The audio device "${err.name}" is not connected.`;
+    default: return 'Audio playback is unavailable.';
+  }
+};
+
+export const describeError = (err: GaweError) => {
+  switch (err.kind) {
+    case 'NoSessionLoaded': return 'No session is loaded.';
+    case 'AlreadyRunning': return 'The timer is already running.';
+    case 'NotRunning': return 'No task is running.';
+    case 'SessionNotFound': return `There is no saved session named "${err.session}".`;
+    case 'InvalidSession': return `This session can't be started: ${err.message}`;
+    case 'Audio': return describeAudioError(err.error);
+    case 'Io': return `Your settings could not be saved: ${err.message}`;
+    case 'Window': return `The window could not be opened: ${err.message}`;
+    default: return 'Something went wrong.';
+  }
+};
@@ -1,10 +1,15 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import { MiniTimer } from "./pages/MiniTimer";
 import "./index.css";
 
+// The mini timer window loads the same page but only shows the countdown
+const isMiniWindow = getCurrentWindow().label === "mini";
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {isMiniWindow ? <MiniTimer /> : <App />}
   </React.StrictMode>,
 );
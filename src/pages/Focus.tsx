@@ -2,14 +2,15 @@ import { useEffect, useState, useRef } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { TimerUpdate } from '../types';
+import { GaweError, TimerUpdate } from '../types';
+import { describeError } from '../lib/errors';
 import { Play, Pause, Square, Maximize, Minimize, Music, Volume2, Edit } from 'lucide-react';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { useSettingsStore } from '../stores/settingsStore';
 import { Button } from '../components/ui/Button';
 import { motion, AnimatePresence } from 'framer-motion';
 
-import { ask } from '@tauri-apps/plugin-dialog';
+import { ask, message } from '@tauri-apps/plugin-dialog';
 
 export const Focus = () => {
   const navigate = useNavigate();
@@ -45,17 +46,32 @@ export const Focus = () => {
       navigate('/');
     });
 
+    // Timer was auto-paused by a screen lock or suspend
+    const unlistenResume = listen<TimerUpdate>('resume-prompt', async (event) => {
+      setTimerState(event.payload);
+      const resume = await ask('Your session was paused while you were away. Resume now?', {
+        title: 'Welcome Back',
+        kind: 'info'
+      });
+      if (resume) {
+        await invoke('start_timer');
+        setTimerState(prev => prev ? { ...prev, is_running: true } : null);
+      }
+    });
+
     return () => {
       unlistenUpdate.then(f => f());
       unlistenFinish.then(f => f());
+      unlistenResume.then(f => f());
       invoke('stop_music').catch(console.error);
     };
 
   }, [navigate]);
 
-  // Auto-play music logic (start when session starts; stop when session stops)
+  // Auto-play music logic (start when session starts; stop when session stops).
+  // Sessions with per-task audio are switched by the timer itself.
   useEffect(() => {
-    if (!settings.musicAutoPlay || !settings.musicFilePath) return;
+    if (!settings.musicAutoPlay || !settings.musicFilePath || timerState?.has_task_audio) return;
 
     const shouldPlay = Boolean(timerState?.is_running);
 
@@ -69,7 +85,7 @@ export const Focus = () => {
       setIsMusicPlaying(false);
       hasAutoPlayed.current = false;
     }
-  }, [timerState?.is_running, settings.musicAutoPlay, settings.musicFilePath]);
+  }, [timerState?.is_running, timerState?.has_task_audio, settings.musicAutoPlay, settings.musicFilePath]);
 
   useEffect(() => {
     invoke('set_volume', { volume: settings.musicVolume }).catch(console.error);
@@ -85,7 +101,15 @@ export const Focus = () => {
       // Optimistic update
       setTimerState(prev => prev ? { ...prev, is_running: false } : null);
     } else {
-      await invoke('start_timer');
+      try {
+        await invoke('start_timer');
+      } catch (err) {
+        // Already started elsewhere (tray, shortcut); just catch up
+        if ((err as GaweError).kind !== 'AlreadyRunning') {
+          await message(describeError(err as GaweError), { title: 'Could Not Start', kind: 'error' });
+          return;
+        }
+      }
       setTimerState(prev => prev ? { ...prev, is_running: true } : null);
     }
   };
@@ -104,8 +128,12 @@ export const Focus = () => {
       setIsMusicPlaying(false);
     } else {
       if (settings.musicFilePath) {
-        await invoke('play_music', { filePath: settings.musicFilePath });
-        setIsMusicPlaying(true);
+        try {
+          await invoke('play_music', { filePath: settings.musicFilePath });
+          setIsMusicPlaying(true);
+        } catch (err) {
+          await message(describeError(err as GaweError), { title: 'Playback Failed', kind: 'error' });
+        }
       } else {
         if (confirm("No music file selected. Go to settings?")) {
           navigate('/settings');
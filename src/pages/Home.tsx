@@ -3,11 +3,12 @@ import { Layout } from '../components/Layout';
 import { useNavigate } from 'react-router-dom';
 import { Play, Trash2, Edit, Clock, List, Plus, PlusSquare } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { Session } from '../types';
+import { GaweError, Session } from '../types';
+import { describeError } from '../lib/errors';
 import { Card, CardHeader, CardTitle, CardContent } from '../components/ui/Card';
 import { Button } from '../components/ui/Button';
 import { motion } from 'framer-motion';
-import { confirm } from '@tauri-apps/plugin-dialog';
+import { confirm, message } from '@tauri-apps/plugin-dialog';
 
 export const Home = () => {
   const { sessions, deleteSession } = useSessionStore();
@@ -32,7 +33,7 @@ export const Home = () => {
       await invoke('start_timer');
       navigate('/focus');
     } catch (error) {
-      console.error('Failed to start session:', error);
+      await message(describeError(error as GaweError), { title: 'Could Not Start', kind: 'error' });
     }
   };
 
@@ -0,0 +1,68 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Pause, Play, X } from 'lucide-react';
+import { TimerUpdate } from '../types';
+
+const formatTime = (seconds: number) => {
+  const m = Math.floor(seconds / 60);
+  const s = seconds % 60;
+  return `${m.toString().padStart(2, '0')}:${s.toString().padStart(2, '0')}`;
+};
+
+// Rendered on its own in the always-on-top "mini" window, without the app's routes
+export const MiniTimer = () => {
+  const [timerState, setTimerState] = useState<TimerUpdate | null>(null);
+
+  useEffect(() => {
+    invoke<TimerUpdate | null>('get_timer_status').then(setTimerState).catch(console.error);
+
+    const unlistenUpdate = listen<TimerUpdate>('timer-update', (event) => {
+      setTimerState(event.payload);
+    });
+    const unlistenFinish = listen('session-finished', () => {
+      setTimerState(null);
+    });
+
+    return () => {
+      unlistenUpdate.then(f => f());
+      unlistenFinish.then(f => f());
+    };
+  }, []);
+
+  const toggleTimer = () => {
+    invoke(timerState?.is_running ? 'pause_timer' : 'start_timer').catch(console.error);
+  };
+
+  const accentColor = timerState?.is_break ? 'text-green-400' : 'text-blue-400';
+
+  return (
+    <div data-tauri-drag-region className="h-screen w-screen flex items-center gap-3 px-4 bg-background text-white select-none rounded-xl border border-white/10">
+      <div data-tauri-drag-region className="flex-1 min-w-0">
+        <div data-tauri-drag-region className={`text-xs font-medium uppercase tracking-widest truncate ${accentColor}`}>
+          {timerState ? timerState.current_task_name : 'No session'}
+        </div>
+        <div data-tauri-drag-region className="text-4xl font-bold tabular-nums leading-tight">
+          {timerState ? formatTime(timerState.remaining_seconds) : '--:--'}
+        </div>
+      </div>
+
+      {timerState && (
+        <button
+          onClick={toggleTimer}
+          className="p-2 rounded-full bg-white text-black hover:bg-gray-200 transition-colors cursor-pointer"
+          title={timerState.is_running ? 'Pause' : 'Start'}
+        >
+          {timerState.is_running ? <Pause size={18} fill="currentColor" /> : <Play size={18} fill="currentColor" />}
+        </button>
+      )}
+      <button
+        onClick={() => invoke('toggle_mini_timer').catch(console.error)}
+        className="self-start mt-2 text-gray-500 hover:text-white transition-colors cursor-pointer"
+        title="Close"
+      >
+        <X size={14} />
+      </button>
+    </div>
+  );
+};
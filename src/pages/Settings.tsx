@@ -1,28 +1,53 @@
-import { useEffect } from 'react';
+import { useEffect, useState } from 'react';
 import { Layout } from '../components/Layout';
 import { useSettingsStore } from '../stores/settingsStore';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
-import { FolderOpen, Music, Volume2, Monitor } from 'lucide-react';
+import { FolderOpen, Music, Volume2, Monitor, Power, Keyboard } from 'lucide-react';
 import { Card, CardHeader, CardTitle, CardContent, CardDescription } from '../components/ui/Card';
 import { Button } from '../components/ui/Button';
 import { Switch } from '../components/ui/Switch';
 import { Input } from '../components/ui/Input';
+import { CloseBehavior, HotkeyAction, HotkeyConflict, HotkeySettings } from '../types';
+
+const HOTKEY_LABELS: Record<HotkeyAction, string> = {
+    'start-pause': 'Start / Pause',
+    'skip': 'Skip Task',
+    'log-interruption': 'Log Interruption',
+    'pause-music': 'Play/Pause Music',
+    'toggle-mini-timer': 'Mini Timer',
+};
 
 export const Settings = () => {
     const settings = useSettingsStore();
+    const [closeBehavior, setCloseBehavior] = useState<CloseBehavior>('minimize-to-tray');
+    const [hotkeys, setHotkeys] = useState<HotkeySettings>({ bindings: {} });
+    const [hotkeyConflicts, setHotkeyConflicts] = useState<HotkeyConflict[]>([]);
 
     useEffect(() => {
         settings.loadSettings();
+        invoke<CloseBehavior>('get_close_behavior').then(setCloseBehavior).catch(console.error);
+        invoke<HotkeySettings>('get_hotkey_settings').then(setHotkeys).catch(console.error);
+        invoke<HotkeyConflict[]>('get_hotkey_conflicts').then(setHotkeyConflicts).catch(console.error);
     }, []);
 
-    // Sync activity settings with backend
-    useEffect(() => {
-        invoke('set_activity_monitoring', { 
-            enabled: settings.activityMonitoring, 
-            threshold: settings.activityThreshold 
-        });
-    }, [settings.activityMonitoring, settings.activityThreshold]);
+    const handleHotkeyChange = (action: HotkeyAction, accelerator: string) => {
+        setHotkeys({ bindings: { ...hotkeys.bindings, [action]: accelerator } });
+    };
+
+    const saveHotkeys = async () => {
+        try {
+            setHotkeyConflicts(await invoke<HotkeyConflict[]>('set_hotkey_settings', { hotkeys }));
+        } catch (e) {
+            console.error(e);
+        }
+    };
+
+    const handleCloseBehavior = async (minimize: boolean) => {
+        const behavior: CloseBehavior = minimize ? 'minimize-to-tray' : 'quit';
+        setCloseBehavior(behavior);
+        await invoke('set_close_behavior', { behavior });
+    };
 
     // Sync volume with backend (if music is playing)
     useEffect(() => {
@@ -34,7 +59,7 @@ export const Settings = () => {
             multiple: false,
             filters: [{
                 name: 'Audio',
-                extensions: ['mp3', 'wav', 'ogg', 'flac']
+                extensions: ['mp3', 'wav', 'ogg', 'flac', 'm3u', 'm3u8', 'pls']
             }]
         });
         if (file) {
@@ -165,6 +190,69 @@ export const Settings = () => {
                         </div>
                     </CardContent>
                 </Card>
+
+                {/* Window */}
+                <Card>
+                    <CardHeader>
+                        <div className="flex items-center gap-3 mb-2">
+                             <div className="p-2 bg-green-500/10 rounded-lg text-green-400">
+                                <Power size={20} />
+                             </div>
+                             <div>
+                                <CardTitle>Closing the Window</CardTitle>
+                                <CardDescription>Choose what happens when you close GaWe</CardDescription>
+                             </div>
+                        </div>
+                    </CardHeader>
+                    <CardContent>
+                        <div className="flex items-center justify-between p-4 rounded-xl bg-surface border border-white/5">
+                            <div>
+                                <label className="block font-medium">Keep Running in the Tray</label>
+                                <p className="text-sm text-gray-400">Closing with a session loaded hides the window. Otherwise GaWe quits, asking first whether to save a running session</p>
+                            </div>
+                            <Switch 
+                                checked={closeBehavior === 'minimize-to-tray'} 
+                                onCheckedChange={handleCloseBehavior} 
+                            />
+                        </div>
+                    </CardContent>
+                </Card>
+
+                {/* Global Shortcuts */}
+                <Card>
+                    <CardHeader>
+                        <div className="flex items-center gap-3 mb-2">
+                             <div className="p-2 bg-orange-500/10 rounded-lg text-orange-400">
+                                <Keyboard size={20} />
+                             </div>
+                             <div>
+                                <CardTitle>Global Shortcuts</CardTitle>
+                                <CardDescription>Work from any app, e.g. CmdOrCtrl+Alt+P; leave empty to turn one off</CardDescription>
+                             </div>
+                        </div>
+                    </CardHeader>
+                    <CardContent className="space-y-3">
+                        {(Object.keys(HOTKEY_LABELS) as HotkeyAction[]).map((action) => {
+                            const conflict = hotkeyConflicts.find((c) => c.action === action);
+                            return (
+                                <div key={action} className="flex items-center justify-between gap-4 p-4 rounded-xl bg-surface border border-white/5">
+                                    <div>
+                                        <label className="block font-medium">{HOTKEY_LABELS[action]}</label>
+                                        {conflict && <p className="text-sm text-red-400">{conflict.reason}</p>}
+                                    </div>
+                                    <Input
+                                        type="text"
+                                        value={hotkeys.bindings[action] || ''}
+                                        onChange={(e) => handleHotkeyChange(action, e.target.value)}
+                                        onBlur={saveHotkeys}
+                                        placeholder="None"
+                                        className="w-56 font-mono"
+                                    />
+                                </div>
+                            );
+                        })}
+                    </CardContent>
+                </Card>
             </div>
         </Layout>
     );
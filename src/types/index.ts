@@ -1,10 +1,19 @@
 export type TaskType = 'Work' | 'Break';
 
+export type AmbientSound = 'white' | 'pink' | 'brown' | 'rain' | 'binaural-focus' | 'binaural-relax';
+
+export type TaskAudio =
+  | { kind: 'silence' }
+  // Paths, folders, playlists or `library:<id>` track references
+  | { kind: 'music'; sources: string[] }
+  | { kind: 'ambient'; sound: AmbientSound };
+
 export interface Task {
   id: string;
   name: string;
   duration_minutes: number;
   task_type: TaskType;
+  audio?: TaskAudio | null;
 }
 
 export interface Session {
@@ -12,6 +21,8 @@ export interface Session {
   name: string;
   tasks: Task[];
   created_at: string;
+  work_audio?: TaskAudio | null;
+  break_audio?: TaskAudio | null;
 }
 
 export interface TimerUpdate {
@@ -21,4 +32,146 @@ export interface TimerUpdate {
   current_task_name: string;
   is_break: boolean;
   session_id: string;
+  has_task_audio: boolean;
+}
+
+export interface ActivitySettings {
+  enabled: boolean;
+  threshold_seconds: number;
+}
+
+export type AudioError =
+  | { kind: 'FileNotFound'; path: string }
+  | { kind: 'UnsupportedFormat'; path: string }
+  | { kind: 'Io'; path: string; message: string }
+  | { kind: 'EmptyPlaylist' }
+  | { kind: 'NotPlaying' }
+  | { kind: 'SeekFailed'; message: string }
+  | { kind: 'NoOutputDevice'; message: string }
+  | { kind: 'DeviceNotFound'; name: string }
+  | { kind: 'Unavailable' };
+
+// Rejection value of every backend command
+export type GaweError =
+  | { kind: 'NoSessionLoaded' }
+  | { kind: 'AlreadyRunning' }
+  | { kind: 'NotRunning' }
+  | { kind: 'SessionNotFound'; session: string }
+  | { kind: 'InvalidSession'; message: string }
+  | { kind: 'Audio'; error: AudioError }
+  | { kind: 'Io'; message: string }
+  | { kind: 'Window'; message: string }
+  | { kind: 'InvalidRequest'; message: string };
+
+export type AdvanceReason = 'completed' | 'skipped';
+
+// Payload of `gawe-event`; each event also goes out under its older name
+export type GaweEvent =
+  | ({ type: 'timer-update' } & TimerUpdate)
+  | { type: 'session-started'; session_id: string }
+  | {
+      type: 'task-changed';
+      session_id: string;
+      task_id: string;
+      task_name: string;
+      task_index: number;
+      is_break: boolean;
+      reason: AdvanceReason;
+    }
+  | { type: 'session-finished'; session_id: string; reason: AdvanceReason }
+  | ({ type: 'resume-prompt' } & TimerUpdate)
+  | { type: 'activity-warning'; idle_seconds: number }
+  | { type: 'distraction-detected'; app: string; session_id: string; task_id: string; task_name: string }
+  | { type: 'interruption-logged'; session_id: string; task_id: string; task_name: string; interruptions: number }
+  | { type: 'power'; event: 'Locked' | 'Unlocked' | 'Suspending' | 'Resumed' }
+  | { type: 'now-playing'; path: string; title: string; index: number; total: number }
+  | { type: 'audio-error'; error: AudioError }
+  | { type: 'audio-output-changed'; device: string }
+  | { type: 'library-updated'; tracks: number }
+  | ({ type: 'activity-settings-changed' } & ActivitySettings)
+  | ({ type: 'distraction-settings-changed' } & DistractionSettings)
+  | ({ type: 'sound-settings-changed' } & SoundSettings)
+  | ({ type: 'announcement-settings-changed' } & AnnouncementSettings)
+  | ({ type: 'fade-settings-changed' } & FadeSettings)
+  | ({ type: 'tick-settings-changed' } & TickSettings)
+  | ({ type: 'hotkey-settings-changed' } & HotkeySettings);
+
+export type EventEnvelope = GaweEvent & {
+  version: number;
+  timestamp: string;
+};
+
+export interface DistractionSettings {
+  enabled: boolean;
+  rules: { allow: string[]; deny: string[] };
+}
+
+export type SoundChoice =
+  | { kind: 'chime' }
+  | { kind: 'bundled'; name: string }
+  | { kind: 'file'; path: string }
+  | { kind: 'silent' };
+
+export interface SoundSettings {
+  volume: number;
+  duck_level: number;
+  work_start: SoundChoice;
+  break_start: SoundChoice;
+  session_finish: SoundChoice;
+  warning: SoundChoice;
+}
+
+export interface FadeSettings {
+  fade_in_ms: number;
+  fade_out_ms: number;
+  pause_fade_ms: number;
+  crossfade_ms: number;
+}
+
+export interface OutputDevice {
+  name: string;
+  is_default: boolean;
+}
+
+export type TickSample =
+  | { kind: 'mechanical' }
+  | { kind: 'soft' }
+  | { kind: 'woodblock' }
+  | { kind: 'file'; path: string };
+
+export interface TickSettings {
+  enabled_for: TaskType[];
+  volume: number;
+  sample: TickSample;
+}
+
+export type Announcement = 'work-start' | 'break-start' | 'five-minutes-left' | 'one-minute-left' | 'session-complete';
+
+export interface AnnouncementSettings {
+  enabled: Announcement[];
+  volume: number;
+}
+
+export interface Track {
+  id: string;
+  path: string;
+  title: string;
+  artist: string | null;
+  album: string | null;
+  duration_ms: number | null;
+  modified: number;
+}
+
+export type CloseBehavior = 'minimize-to-tray' | 'quit';
+
+export type HotkeyAction = 'start-pause' | 'skip' | 'log-interruption' | 'pause-music' | 'toggle-mini-timer';
+
+export interface HotkeySettings {
+  bindings: Partial<Record<HotkeyAction, string>>;
+}
+
+export interface HotkeyConflict {
+  action: HotkeyAction;
+  accelerator: string;
+  reason: string;
 }